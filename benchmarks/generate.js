@@ -0,0 +1,76 @@
+'use strict';
+
+// Generates the synthetic inputs that, unlike the real-world ones, are cheaper to
+// describe than to store. The output is the same on every run.
+
+const fs = require('fs');
+
+// Small seeded PRNG (mulberry32), so that numbers look random but are reproducible.
+function random(seed) {
+	return () => {
+		seed = (seed + 0x6d2b79f5) | 0;
+		let t = Math.imul(seed ^ (seed >>> 15), 1 | seed);
+		t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+		return ((t ^ (t >>> 14)) >>> 0) / 2 ** 32;
+	};
+}
+
+// 10 levels deep with two children per node.
+function configTree(name = 'root', level = 0, index = 0) {
+	return {
+		name,
+		enabled: index % 2 === 0,
+		weight: Number((1 / (level + 1)).toFixed(6)),
+		retries: (level * 3 + index) % 5,
+		timeout_ms: 1000 * (1 + (index % 3)),
+		tags: [`level-${level}`, `tier-${index % 3}`],
+		children:
+			level < 9
+				? [0, 1].map(i => configTree(level === 0 ? `node-${i}` : `${name}-${i}`, level + 1, i))
+				: []
+	};
+}
+
+function wideStruct() {
+	const next = random(42);
+	const int = (min, max) => min + Math.floor(next() * (max - min + 1));
+	const pick = values => values[int(0, values.length - 1)];
+
+	return Array.from({ length: 1000 }, (_, id) => ({
+		id,
+		parent_id: id === 0 ? 0 : int(0, id - 1),
+		revision: int(1, 100),
+		priority: int(0, 255),
+		attempts: int(0, 1000),
+		offset: int(-100000, 100000),
+		delta: int(-1e12, 1e12),
+		created_at: int(1.6e12, 1.7e12),
+		score: Number((next() * 100).toFixed(3)),
+		ratio: int(0, 16) / 4,
+		latitude: Number((next() * 180 - 90).toFixed(6)),
+		longitude: Number((next() * 360 - 180).toFixed(6)),
+		active: next() < 0.5,
+		archived: next() < 0.5,
+		verified: next() < 0.5,
+		name: `item-${id}`,
+		kind: pick(['document', 'image', 'video', 'folder']),
+		owner: `user-${int(0, 99)}`,
+		region: pick(['us-east', 'us-west', 'eu-west', 'ap-south']),
+		status: pick(['draft', 'published', 'archived'])
+	}));
+}
+
+// A long flat sequence of small integers.
+function vec100k() {
+	return Array.from({ length: 100000 }, (_, i) => (i * 7) % 100003);
+}
+
+const inputs = {
+	config_tree: configTree(),
+	vec_100k: vec100k(),
+	wide_struct: wideStruct()
+};
+
+for (const [name, value] of Object.entries(inputs)) {
+	fs.writeFileSync(`${__dirname}/${name}.json`, JSON.stringify(value));
+}
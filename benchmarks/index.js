@@ -8,7 +8,7 @@ let suites = {
 	serialize: new Suite('serialize')
 };
 
-for (let input of ['canada', 'citm_catalog', 'twitter']) {
+for (let input of ['canada', 'citm_catalog', 'config_tree', 'twitter', 'vec_100k', 'wide_struct']) {
 	const json = require(`./${input}.json`);
 
 	for (const lib of ['serde_json', 'serde_wasm_bindgen']) {
@@ -23,6 +23,20 @@ for (let input of ['canada', 'citm_catalog', 'twitter']) {
 	}
 }
 
+// Byte buffers can't be passed through JSON, so only serde-wasm-bindgen is measured.
+{
+	const bytes = new Uint8Array(64 << 20);
+
+	const parse = benches.parse_bytes_64m_with_serde_wasm_bindgen;
+	suites.parse.add('bytes_64m x serde_wasm_bindgen', () => parse(bytes).free());
+
+	const serialize = benches.serialize_bytes_64m_with_serde_wasm_bindgen;
+	let parsed = parse(bytes);
+	suites.serialize.add('bytes_64m x serde_wasm_bindgen', () => serialize(parsed), {
+		onComplete: () => parsed.free()
+	});
+}
+
 function runSuite(suite) {
 	console.log('='.repeat(suite.name.length));
 	console.log(suite.name);
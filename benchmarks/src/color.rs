@@ -8,7 +8,7 @@ use serde::ser::{Serialize, Serializer};
 #[derive(Clone, Copy)]
 pub struct Color(u32);
 
-const HEX_LUT: &'static [u8] = b"\
+const HEX_LUT: &[u8] = b"\
       000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F\
       202122232425262728292A2B2C2D2E2F303132333435363738393A3B3C3D3E3F\
       404142434445464748494A4B4C4D4E4F505152535455565758595A5B5C5D5E5F\
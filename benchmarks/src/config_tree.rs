@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+// A synthetic configuration tree, 10 levels deep with two children per node,
+// to measure the cost of deeply nested structs as opposed to the wide and
+// mostly flat shapes of the other inputs.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigTree {
+    pub name: String,
+    pub enabled: bool,
+    pub weight: f64,
+    pub retries: u32,
+    pub timeout_ms: u32,
+    pub tags: Vec<String>,
+    pub children: Vec<ConfigTree>,
+}
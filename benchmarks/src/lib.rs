@@ -7,7 +7,9 @@ mod prim_str;
 
 mod canada;
 mod citm_catalog;
+mod config_tree;
 mod twitter;
+mod wide_struct;
 
 #[wasm_bindgen]
 #[derive(Serialize, Deserialize)]
@@ -17,10 +19,28 @@ pub struct Canada(canada::Canada);
 #[derive(Serialize, Deserialize)]
 pub struct CitmCatalog(citm_catalog::CitmCatalog);
 
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
+pub struct ConfigTree(config_tree::ConfigTree);
+
 #[wasm_bindgen]
 #[derive(Serialize, Deserialize)]
 pub struct Twitter(twitter::Twitter);
 
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
+pub struct WideStruct(Vec<wide_struct::WideStruct>);
+
+// A flat sequence to measure the cost of large arrays.
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
+pub struct Vec100k(Vec<u32>);
+
+// A large byte buffer to measure the cost of copying bytes in and out of the Wasm memory.
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize)]
+pub struct Bytes64m(serde_bytes::ByteBuf);
+
 #[wasm_bindgen(start)]
 pub fn init_console() {
     console_error_panic_hook::set_once();
@@ -41,6 +61,7 @@ pub fn parse_canada_with_serde_wasm_bindgen(input: JsValue) -> Canada {
 }
 
 #[cfg(feature = "serde-json")]
+#[allow(deprecated)]
 #[wasm_bindgen]
 pub fn parse_canada_with_serde_json(input: JsValue) -> Canada {
     input.into_serde().unwrap()
@@ -53,6 +74,7 @@ pub fn serialize_canada_with_serde_wasm_bindgen(input: &Canada) -> JsValue {
 }
 
 #[cfg(feature = "serde-json")]
+#[allow(deprecated)]
 #[wasm_bindgen]
 pub fn serialize_canada_with_serde_json(input: &Canada) -> JsValue {
     JsValue::from_serde(input).unwrap()
@@ -65,6 +87,7 @@ pub fn parse_citm_catalog_with_serde_wasm_bindgen(input: JsValue) -> CitmCatalog
 }
 
 #[cfg(feature = "serde-json")]
+#[allow(deprecated)]
 #[wasm_bindgen]
 pub fn parse_citm_catalog_with_serde_json(input: JsValue) -> CitmCatalog {
     input.into_serde().unwrap()
@@ -77,6 +100,7 @@ pub fn serialize_citm_catalog_with_serde_wasm_bindgen(input: &CitmCatalog) -> Js
 }
 
 #[cfg(feature = "serde-json")]
+#[allow(deprecated)]
 #[wasm_bindgen]
 pub fn serialize_citm_catalog_with_serde_json(input: &CitmCatalog) -> JsValue {
     JsValue::from_serde(input).unwrap()
@@ -89,6 +113,7 @@ pub fn parse_twitter_with_serde_wasm_bindgen(input: JsValue) -> Twitter {
 }
 
 #[cfg(feature = "serde-json")]
+#[allow(deprecated)]
 #[wasm_bindgen]
 pub fn parse_twitter_with_serde_json(input: JsValue) -> Twitter {
     input.into_serde().unwrap()
@@ -101,7 +126,99 @@ pub fn serialize_twitter_with_serde_wasm_bindgen(input: &Twitter) -> JsValue {
 }
 
 #[cfg(feature = "serde-json")]
+#[allow(deprecated)]
 #[wasm_bindgen]
 pub fn serialize_twitter_with_serde_json(input: &Twitter) -> JsValue {
     JsValue::from_serde(input).unwrap()
 }
+
+#[cfg(feature = "serde-wasm-bindgen")]
+#[wasm_bindgen]
+pub fn parse_config_tree_with_serde_wasm_bindgen(input: JsValue) -> ConfigTree {
+    serde_wasm_bindgen::from_value(input).unwrap()
+}
+
+#[cfg(feature = "serde-json")]
+#[allow(deprecated)]
+#[wasm_bindgen]
+pub fn parse_config_tree_with_serde_json(input: JsValue) -> ConfigTree {
+    input.into_serde().unwrap()
+}
+
+#[cfg(feature = "serde-wasm-bindgen")]
+#[wasm_bindgen]
+pub fn serialize_config_tree_with_serde_wasm_bindgen(input: &ConfigTree) -> JsValue {
+    serde_wasm_bindgen_to_value(input).unwrap()
+}
+
+#[cfg(feature = "serde-json")]
+#[allow(deprecated)]
+#[wasm_bindgen]
+pub fn serialize_config_tree_with_serde_json(input: &ConfigTree) -> JsValue {
+    JsValue::from_serde(input).unwrap()
+}
+
+#[cfg(feature = "serde-wasm-bindgen")]
+#[wasm_bindgen]
+pub fn parse_wide_struct_with_serde_wasm_bindgen(input: JsValue) -> WideStruct {
+    serde_wasm_bindgen::from_value(input).unwrap()
+}
+
+#[cfg(feature = "serde-json")]
+#[allow(deprecated)]
+#[wasm_bindgen]
+pub fn parse_wide_struct_with_serde_json(input: JsValue) -> WideStruct {
+    input.into_serde().unwrap()
+}
+
+#[cfg(feature = "serde-wasm-bindgen")]
+#[wasm_bindgen]
+pub fn serialize_wide_struct_with_serde_wasm_bindgen(input: &WideStruct) -> JsValue {
+    serde_wasm_bindgen_to_value(input).unwrap()
+}
+
+#[cfg(feature = "serde-json")]
+#[allow(deprecated)]
+#[wasm_bindgen]
+pub fn serialize_wide_struct_with_serde_json(input: &WideStruct) -> JsValue {
+    JsValue::from_serde(input).unwrap()
+}
+
+#[cfg(feature = "serde-wasm-bindgen")]
+#[wasm_bindgen]
+pub fn parse_vec_100k_with_serde_wasm_bindgen(input: JsValue) -> Vec100k {
+    serde_wasm_bindgen::from_value(input).unwrap()
+}
+
+#[cfg(feature = "serde-json")]
+#[allow(deprecated)]
+#[wasm_bindgen]
+pub fn parse_vec_100k_with_serde_json(input: JsValue) -> Vec100k {
+    input.into_serde().unwrap()
+}
+
+#[cfg(feature = "serde-wasm-bindgen")]
+#[wasm_bindgen]
+pub fn serialize_vec_100k_with_serde_wasm_bindgen(input: &Vec100k) -> JsValue {
+    serde_wasm_bindgen_to_value(input).unwrap()
+}
+
+#[cfg(feature = "serde-json")]
+#[allow(deprecated)]
+#[wasm_bindgen]
+pub fn serialize_vec_100k_with_serde_json(input: &Vec100k) -> JsValue {
+    JsValue::from_serde(input).unwrap()
+}
+
+// Byte buffers have no JSON representation, so there is no serde-json counterpart.
+#[cfg(feature = "serde-wasm-bindgen")]
+#[wasm_bindgen]
+pub fn parse_bytes_64m_with_serde_wasm_bindgen(input: JsValue) -> Bytes64m {
+    serde_wasm_bindgen::from_value(input).unwrap()
+}
+
+#[cfg(feature = "serde-wasm-bindgen")]
+#[wasm_bindgen]
+pub fn serialize_bytes_64m_with_serde_wasm_bindgen(input: &Bytes64m) -> JsValue {
+    serde_wasm_bindgen_to_value(input).unwrap()
+}
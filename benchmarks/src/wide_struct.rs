@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+// A synthetic list of records with 20 primitive fields each, to measure the
+// per-field cost of wide flat structs without any nested containers.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WideStruct {
+    pub id: u32,
+    pub parent_id: u32,
+    pub revision: u32,
+    pub priority: u8,
+    pub attempts: u16,
+    pub offset: i32,
+    pub delta: i64,
+    pub created_at: u64,
+    pub score: f64,
+    pub ratio: f32,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub active: bool,
+    pub archived: bool,
+    pub verified: bool,
+    pub name: String,
+    pub kind: String,
+    pub owner: String,
+    pub region: String,
+    pub status: String,
+}
@@ -0,0 +1,85 @@
+//! Serializes [`Result`] as a JavaScript-style discriminated union.
+//!
+//! Use with `#[serde(with = "serde_wasm_bindgen::as_js_result")]` on a [`Result`] field to represent
+//! `Ok(value)` as `{ ok: true, value }` and `Err(error)` as `{ ok: false, error }` instead of the
+//! default serde-json compatible `{ Ok: value }` / `{ Err: error }`.
+
+use serde::de::{self, Deserialize};
+use serde::ser::{self, Serialize, SerializeStruct};
+use std::fmt;
+use std::marker::PhantomData;
+
+const FIELDS: &[&str] = &["ok", "value", "error"];
+
+/// Serializes a [`Result`] as `{ ok: true, value }` or `{ ok: false, error }`.
+pub fn serialize<T, E, S>(result: &Result<T, E>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    E: Serialize,
+    S: ser::Serializer,
+{
+    let mut object = serializer.serialize_struct("Result", 2)?;
+    match result {
+        Ok(value) => {
+            object.serialize_field("ok", &true)?;
+            object.serialize_field("value", value)?;
+        }
+        Err(error) => {
+            object.serialize_field("ok", &false)?;
+            object.serialize_field("error", error)?;
+        }
+    }
+    object.end()
+}
+
+/// Deserializes a [`Result`] from `{ ok: true, value }` or `{ ok: false, error }`.
+pub fn deserialize<'de, T, E, D>(deserializer: D) -> Result<Result<T, E>, D::Error>
+where
+    T: Deserialize<'de>,
+    E: Deserialize<'de>,
+    D: de::Deserializer<'de>,
+{
+    deserializer.deserialize_struct("Result", FIELDS, ResultVisitor(PhantomData))
+}
+
+struct ResultVisitor<T, E>(PhantomData<(T, E)>);
+
+impl<'de, T: Deserialize<'de>, E: Deserialize<'de>> de::Visitor<'de> for ResultVisitor<T, E> {
+    type Value = Result<T, E>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an object with a boolean `ok` and either `value` or `error`")
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut ok = None;
+        let mut value = None;
+        let mut error = None;
+
+        // `ok` is listed first in `FIELDS`, so deserializers that go over fields in order let us
+        // skip the payload that doesn't apply without trying to deserialize it.
+        while let Some(key) = map.next_key::<String>()? {
+            match (key.as_str(), ok) {
+                ("ok", _) => ok = Some(map.next_value::<bool>()?),
+                ("value", Some(false)) | ("error", Some(true)) => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+                ("value", _) => value = Some(map.next_value::<T>()?),
+                ("error", _) => error = Some(map.next_value::<E>()?),
+                _ => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+
+        match ok {
+            Some(true) => value
+                .map(Ok)
+                .ok_or_else(|| de::Error::missing_field("value")),
+            Some(false) => error
+                .map(Err)
+                .ok_or_else(|| de::Error::missing_field("error")),
+            None => Err(de::Error::missing_field("ok")),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Serializes [`Duration`] as a single JavaScript number of milliseconds.
+//!
+//! Use with `#[serde(with = "serde_wasm_bindgen::as_millis")]` on a [`Duration`] field to pass it
+//! straight to `setTimeout`-style JavaScript APIs. Any sub-millisecond part is truncated during
+//! serialization, while fractional milliseconds coming from JavaScript are preserved on deserialization.
+//!
+//! This is [`duration_millis`](crate::duration_millis) with whole milliseconds on the way to
+//! JavaScript; use that one to keep sub-millisecond parts as a fraction in both directions.
+
+use serde::{de, ser};
+use std::time::Duration;
+
+/// Serializes a [`Duration`] as a number of whole milliseconds.
+///
+/// Fails if the number of milliseconds exceeds `Number.MAX_SAFE_INTEGER`.
+pub fn serialize<S: ser::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let whole_millis = Duration::new(duration.as_secs(), duration.subsec_millis() * 1_000_000);
+    crate::duration_millis::serialize(&whole_millis, serializer)
+}
+
+/// Deserializes a [`Duration`] from a non-negative number of milliseconds.
+///
+/// Fractional milliseconds are kept with nanosecond precision.
+pub fn deserialize<'de, D: de::Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    crate::duration_millis::deserialize(deserializer)
+}
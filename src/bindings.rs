@@ -1,4 +1,4 @@
-use js_sys::BigInt;
+use js_sys::{BigInt, JsString, Number, Object};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -14,4 +14,10 @@ extern "C" {
 
     #[wasm_bindgen(js_name = BigInt)]
     pub fn bigint_from_i64(x: i64) -> BigInt;
+
+    #[wasm_bindgen(js_name = String)]
+    pub fn number_to_string(x: &Number) -> JsString;
+
+    #[wasm_bindgen(catch, js_namespace = Object, js_name = seal)]
+    pub fn try_seal(x: &Object) -> Result<Object, JsValue>;
 }
@@ -0,0 +1,234 @@
+use serde::de::{self, DeserializeOwned};
+use serde::ser::{self, Serialize};
+use std::fmt;
+
+/// A two-variant unit enum, like `enum State { On, Off }`, that is serialized as a boolean:
+/// `true` for the first variant and `false` for the second one.
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use serde_wasm_bindgen::BoolEnum;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+/// enum State {
+///     On,
+///     Off,
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Light {
+///     state: BoolEnum<State>,
+/// }
+/// ```
+///
+/// Serialization only looks at the index of the unit variant, so it fails for any other value,
+/// but can't tell a two-variant enum apart from the first two variants of a longer one.
+/// Deserialization finds out the variants via the [`Deserialize`](serde::Deserialize)
+/// implementation of the enum, and fails if it doesn't have exactly two unit variants.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BoolEnum<T>(pub T);
+
+/// Error of [`variants`] and [`VariantIndexSerializer`], which also carries the variant names
+/// found by the probe.
+#[derive(Debug)]
+struct Probe(Option<&'static [&'static str]>);
+
+impl fmt::Display for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("BoolEnum requires an enum with exactly two unit variants")
+    }
+}
+
+impl std::error::Error for Probe {}
+
+impl de::Error for Probe {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Probe(None)
+    }
+}
+
+impl ser::Error for Probe {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Probe(None)
+    }
+}
+
+/// A deserializer that only records the variant names of the enum it's asked for.
+struct ProbeDeserializer;
+
+impl<'de> de::Deserializer<'de> for ProbeDeserializer {
+    type Error = Probe;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Probe> {
+        Err(Probe(None))
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Probe> {
+        Err(Probe(Some(variants)))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Returns the two variants of `T`, or an error if it's not an enum with two unit variants.
+fn variants<T: DeserializeOwned>() -> Result<(T, T), Probe> {
+    let variants = match T::deserialize(ProbeDeserializer) {
+        Err(Probe(Some(variants))) if variants.len() == 2 => variants,
+        _ => return Err(Probe(None)),
+    };
+    // Deserializing from a plain string only succeeds for unit variants.
+    let variant = |name| {
+        T::deserialize(de::value::StrDeserializer::<de::value::Error>::new(name))
+            .map_err(|_| Probe(None))
+    };
+    Ok((variant(variants[0])?, variant(variants[1])?))
+}
+
+/// A serializer that only records the index of the unit variant it's given.
+struct VariantIndexSerializer;
+
+macro_rules! reject {
+    ($($name:ident($($ty:ty),*);)*) => {
+        $(fn $name(self, $(_: $ty),*) -> Result<u32, Probe> {
+            Err(Probe(None))
+        })*
+    };
+}
+
+impl ser::Serializer for VariantIndexSerializer {
+    type Ok = u32;
+    type Error = Probe;
+
+    type SerializeSeq = ser::Impossible<u32, Probe>;
+    type SerializeTuple = ser::Impossible<u32, Probe>;
+    type SerializeTupleStruct = ser::Impossible<u32, Probe>;
+    type SerializeTupleVariant = ser::Impossible<u32, Probe>;
+    type SerializeMap = ser::Impossible<u32, Probe>;
+    type SerializeStruct = ser::Impossible<u32, Probe>;
+    type SerializeStructVariant = ser::Impossible<u32, Probe>;
+
+    reject! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<u32, Probe> {
+        Ok(variant_index)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<u32, Probe> {
+        Err(Probe(None))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<u32, Probe> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<u32, Probe> {
+        Err(Probe(None))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Probe> {
+        Err(Probe(None))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Probe> {
+        Err(Probe(None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Probe> {
+        Err(Probe(None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Probe> {
+        Err(Probe(None))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Probe> {
+        Err(Probe(None))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Probe> {
+        Err(Probe(None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Probe> {
+        Err(Probe(None))
+    }
+}
+
+impl<T: Serialize> Serialize for BoolEnum<T> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.serialize(VariantIndexSerializer) {
+            Ok(index @ (0 | 1)) => serializer.serialize_bool(index == 0),
+            _ => Err(ser::Error::custom(Probe(None))),
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned> de::Deserialize<'de> for BoolEnum<T> {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = bool::deserialize(deserializer)?;
+        let (first, second) = variants::<T>().map_err(de::Error::custom)?;
+        Ok(BoolEnum(if value { first } else { second }))
+    }
+}
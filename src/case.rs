@@ -0,0 +1,89 @@
+use std::cell::RefCell;
+
+/// Case convention applied to enum variant names at runtime, same as the corresponding
+/// `#[serde(rename_all = "...")]` rule would do for `PascalCase` variant names.
+///
+/// Unlike the attribute, this applies to all enums uniformly, including ones from other crates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// Keep variant names as they are.
+    #[default]
+    Unchanged,
+    /// `"lowercase"`, e.g. `InProgress` becomes `inprogress`.
+    Lower,
+    /// `"UPPERCASE"`, e.g. `InProgress` becomes `INPROGRESS`.
+    Upper,
+    /// `"camelCase"`, e.g. `InProgress` becomes `inProgress`.
+    Camel,
+    /// `"snake_case"`, e.g. `InProgress` becomes `in_progress`.
+    Snake,
+    /// `"SCREAMING_SNAKE_CASE"`, e.g. `InProgress` becomes `IN_PROGRESS`.
+    ScreamingSnake,
+    /// `"kebab-case"`, e.g. `InProgress` becomes `in-progress`.
+    Kebab,
+    /// `"SCREAMING-KEBAB-CASE"`, e.g. `InProgress` becomes `IN-PROGRESS`.
+    ScreamingKebab,
+}
+
+impl Case {
+    /// Converts a variant name, interning the result so that it can be cached like any other
+    /// static string. The number of leaked strings is bounded by the number of variants.
+    pub(crate) fn apply(self, variant: &'static str) -> &'static str {
+        if self == Case::Unchanged {
+            return variant;
+        }
+        thread_local! {
+            static CACHE: RefCell<fnv::FnvHashMap<(Case, usize, usize), &'static str>> = Default::default();
+        }
+        CACHE.with(|cache| {
+            *cache
+                .borrow_mut()
+                .entry((self, variant.as_ptr() as usize, variant.len()))
+                .or_insert_with(|| Box::leak(self.convert(variant).into_boxed_str()))
+        })
+    }
+
+    /// Finds the original variant name for a converted one, or returns `None` if there is no
+    /// such variant or the case is [`Case::Unchanged`].
+    pub(crate) fn find_variant(
+        self,
+        converted: &str,
+        variants: &'static [&'static str],
+    ) -> Option<&'static str> {
+        if self == Case::Unchanged {
+            return None;
+        }
+        variants
+            .iter()
+            .copied()
+            .find(|&variant| self.apply(variant) == converted)
+    }
+
+    fn convert(self, variant: &str) -> String {
+        let snake = || {
+            let mut snake = String::new();
+            for (i, ch) in variant.char_indices() {
+                if i > 0 && ch.is_uppercase() {
+                    snake.push('_');
+                }
+                snake.push(ch.to_ascii_lowercase());
+            }
+            snake
+        };
+        match self {
+            Case::Unchanged => variant.to_owned(),
+            Case::Lower => variant.to_ascii_lowercase(),
+            Case::Upper => variant.to_ascii_uppercase(),
+            Case::Camel => {
+                let mut chars = variant.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_ascii_lowercase().to_string() + chars.as_str()
+                })
+            }
+            Case::Snake => snake(),
+            Case::ScreamingSnake => snake().to_ascii_uppercase(),
+            Case::Kebab => snake().replace('_', "-"),
+            Case::ScreamingKebab => snake().to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
@@ -1,13 +1,20 @@
 use crate::bindings;
-use js_sys::{Array, ArrayBuffer, BigInt, JsString, Number, Object, Reflect, Symbol, Uint8Array};
-use serde::{de, serde_if_integer128};
+use js_sys::{
+    Array, ArrayBuffer, BigInt, DataView, JsString, Map, Object, Reflect, Symbol, Uint8Array,
+};
+use serde::de;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
 use wasm_bindgen::{JsCast, JsValue};
 
-use super::{static_str_to_js, Error, ObjectExt, Result};
+use super::preserve::{with_handover, PRESERVED_VALUE_MAGIC};
+use super::{static_str_to_js, Case, Error, ObjectExt, Result};
 
 /// Provides [`de::SeqAccess`] from any JS iterator.
 struct SeqAccess {
     iter: js_sys::IntoIter,
+    config: Rc<Config>,
 }
 
 impl<'de> de::SeqAccess<'de> for SeqAccess {
@@ -18,7 +25,7 @@ impl<'de> de::SeqAccess<'de> for SeqAccess {
         seed: T,
     ) -> Result<Option<T::Value>> {
         Ok(match self.iter.next().transpose()? {
-            Some(value) => Some(seed.deserialize(Deserializer::from(value))?),
+            Some(value) => Some(seed.deserialize(Deserializer::with_config(value, &self.config))?),
             None => None,
         })
     }
@@ -28,6 +35,32 @@ impl<'de> de::SeqAccess<'de> for SeqAccess {
 struct MapAccess {
     iter: js_sys::IntoIter,
     next_value: Option<Deserializer>,
+    config: Rc<Config>,
+    /// Whether the produced pairs need to be validated, which is only unnecessary for sources
+    /// that always produce valid ones, like `Map` and `Object.entries`.
+    check_pairs: bool,
+}
+
+impl MapAccess {
+    fn next_pair(&mut self) -> Result<Option<(Deserializer, Deserializer)>> {
+        let pair = match self.iter.next().transpose()? {
+            Some(pair) => pair,
+            None => return Ok(None),
+        };
+        if self.check_pairs {
+            match pair.dyn_ref::<Array>() {
+                Some(array) if array.length() == 2 => {}
+                Some(array) => {
+                    return Err(de::Error::invalid_length(
+                        array.length() as _,
+                        &"a [key, value] pair",
+                    ))
+                }
+                None => return Err(Deserializer::from(pair).invalid_type_(&"a [key, value] pair")),
+            }
+        }
+        Ok(Some(convert_pair(pair, &self.config)))
+    }
 }
 
 impl<'de> de::MapAccess<'de> for MapAccess {
@@ -36,9 +69,8 @@ impl<'de> de::MapAccess<'de> for MapAccess {
     fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
         debug_assert!(self.next_value.is_none());
 
-        Ok(match self.iter.next().transpose()? {
-            Some(pair) => {
-                let (key, value) = convert_pair(pair);
+        Ok(match self.next_pair()? {
+            Some((key, value)) => {
                 self.next_value = Some(value);
                 Some(seed.deserialize(key)?)
             }
@@ -57,30 +89,122 @@ impl<'de> de::MapAccess<'de> for MapAccess {
     ) -> Result<Option<(K::Value, V::Value)>> {
         debug_assert!(self.next_value.is_none());
 
-        Ok(match self.iter.next().transpose()? {
-            Some(pair) => {
-                let (key, value) = convert_pair(pair);
-                Some((kseed.deserialize(key)?, vseed.deserialize(value)?))
-            }
+        Ok(match self.next_pair()? {
+            Some((key, value)) => Some((kseed.deserialize(key)?, vseed.deserialize(value)?)),
             None => None,
         })
     }
 }
 
+/// Where [`ObjectAccess`] looks up the fields of a struct.
+enum FieldSource {
+    Object(ObjectExt),
+    Map(Map),
+}
+
+impl FieldSource {
+    fn get(&self, key: &JsString) -> JsValue {
+        match self {
+            FieldSource::Object(obj) => obj.get(key),
+            FieldSource::Map(map) => map.get(key),
+        }
+    }
+}
+
 struct ObjectAccess {
-    obj: ObjectExt,
+    obj: FieldSource,
     fields: &'static [&'static str],
+    config: Rc<Config>,
+    field_errors: Option<Rc<FieldErrors>>,
+    skipped_keys: Vec<&'static str>,
+    present_fields: Option<Rc<PresentFields>>,
+}
+
+impl ObjectAccess {
+    /// Skips the tag fields of flattened struct variants, as well as fields that failed during
+    /// the previous attempts of [`crate::try_from_value`].
+    fn skip_fields(&mut self) {
+        while let Some((&field, fields)) = self.fields.split_first() {
+            let skipped = self.skipped_keys.contains(&field)
+                || self
+                    .field_errors
+                    .as_ref()
+                    .is_some_and(|field_errors| field_errors.failed.borrow().contains(&field));
+            if !skipped {
+                break;
+            }
+            self.fields = fields;
+        }
+    }
+
+    fn deserialize_field<'de, V: de::DeserializeSeed<'de>>(
+        &self,
+        field: &'static str,
+        seed: V,
+    ) -> Result<V::Value> {
+        let value = self.obj.get(&static_str_to_js(field));
+        if let Some(present_fields) = &self.present_fields {
+            if !value.is_undefined() {
+                present_fields.borrow_mut().insert(field);
+            }
+        }
+        let result = seed.deserialize(Deserializer::with_config(value, &self.config));
+        if let (Err(_), Some(field_errors)) = (&result, &self.field_errors) {
+            field_errors.failed.borrow_mut().push(field);
+        }
+        result
+    }
+}
+
+/// Fields of the top-level struct that were present in [`crate::from_value_tracking_presence`].
+pub(crate) type PresentFields = RefCell<HashSet<&'static str>>;
+
+/// Fields of the top-level struct that failed to deserialize in [`crate::try_from_value`].
+#[derive(Default)]
+pub(crate) struct FieldErrors {
+    failed: RefCell<Vec<&'static str>>,
 }
 
-fn str_deserializer(s: &str) -> de::value::StrDeserializer<Error> {
+impl FieldErrors {
+    /// Returns the field that failed since the given number of failures was recorded, if any.
+    pub(crate) fn failed_since(&self, count: usize) -> Option<&'static str> {
+        self.failed.borrow().get(count).copied()
+    }
+
+    pub(crate) fn failed_count(&self) -> usize {
+        self.failed.borrow().len()
+    }
+
+    /// Failed fields are hidden from the struct on the next attempt, so they might be reported
+    /// as missing even though they aren't.
+    pub(crate) fn is_missing_failed_field(&self, error: &Error) -> bool {
+        let error = error.to_string();
+        self.failed
+            .borrow()
+            .iter()
+            .any(|&field| <Error as de::Error>::missing_field(field).to_string() == error)
+    }
+}
+
+fn str_deserializer(s: &str) -> de::value::StrDeserializer<'_, Error> {
     de::IntoDeserializer::into_deserializer(s)
 }
 
+/// Same as `Number.isSafeInteger`, but checked on the Rust side to avoid an extra JS call for
+/// every integer that we deserialize.
+fn is_safe_integer(v: f64) -> bool {
+    const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+    // `NaN` and infinities are rejected by these checks as well.
+    v.trunc() == v && v.abs() <= MAX_SAFE_INTEGER
+}
+
 impl<'de> de::MapAccess<'de> for ObjectAccess {
     type Error = Error;
 
     fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
-        Ok(match self.fields.get(0) {
+        self.skip_fields();
+        Ok(match self.fields.first() {
             Some(&field) => Some(seed.deserialize(str_deserializer(field))?),
             None => None,
         })
@@ -89,8 +213,7 @@ impl<'de> de::MapAccess<'de> for ObjectAccess {
     fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
         let (field, fields) = self.fields.split_first().unwrap();
         self.fields = fields;
-        let value = self.obj.get(static_str_to_js(field));
-        seed.deserialize(Deserializer::from(value))
+        self.deserialize_field(field, seed)
     }
 
     fn next_entry_seed<K: de::DeserializeSeed<'de>, V: de::DeserializeSeed<'de>>(
@@ -98,12 +221,13 @@ impl<'de> de::MapAccess<'de> for ObjectAccess {
         kseed: K,
         vseed: V,
     ) -> Result<Option<(K::Value, V::Value)>> {
+        self.skip_fields();
         Ok(match self.fields.split_first() {
             Some((&field, fields)) => {
                 self.fields = fields;
                 Some((
                     kseed.deserialize(str_deserializer(field))?,
-                    vseed.deserialize(Deserializer::from(self.obj.get(static_str_to_js(field))))?,
+                    self.deserialize_field(field, vseed)?,
                 ))
             }
             None => None,
@@ -112,12 +236,15 @@ impl<'de> de::MapAccess<'de> for ObjectAccess {
 }
 
 /// Provides [`serde::de::EnumAccess`] from given JS values for the `tag` and the `payload`.
-struct EnumAccess {
-    tag: Deserializer,
+///
+/// The tag is usually a JS string, but can be an index from a JS number too.
+struct EnumAccess<T = Deserializer> {
+    tag: T,
     payload: Deserializer,
+    raw: Option<RawVariant>,
 }
 
-impl<'de> de::EnumAccess<'de> for EnumAccess {
+impl<'de, T: de::Deserializer<'de, Error = Error>> de::EnumAccess<'de> for EnumAccess<T> {
     type Error = Error;
     type Variant = Deserializer;
 
@@ -125,36 +252,445 @@ impl<'de> de::EnumAccess<'de> for EnumAccess {
         self,
         seed: V,
     ) -> Result<(V::Value, Self::Variant)> {
-        Ok((seed.deserialize(self.tag)?, self.payload))
+        let raw = match self.raw {
+            Some(raw) => raw,
+            None => return Ok((seed.deserialize(self.tag)?, self.payload)),
+        };
+        let seed = RawVariantSeed {
+            seed,
+            name: raw.name,
+            variants: raw.variants,
+        };
+        let (variant, is_raw) = de::DeserializeSeed::deserialize(seed, self.tag)?;
+        let payload = if is_raw {
+            Deserializer::with_config(raw.value, &self.payload.config)
+        } else {
+            self.payload
+        };
+        Ok((variant, payload))
+    }
+}
+
+/// The whole enum value, used as the payload of the [`Deserializer::raw_variant`] in case
+/// the tag turns out to hold an unknown variant.
+struct RawVariant {
+    name: &'static str,
+    variants: &'static [&'static str],
+    value: JsValue,
+}
+
+/// Deserializes the variant tag, replacing unknown variants with the raw one.
+/// Also returns whether that happened.
+struct RawVariantSeed<S> {
+    seed: S,
+    name: &'static str,
+    variants: &'static [&'static str],
+}
+
+impl<'de, S: de::DeserializeSeed<'de>> de::DeserializeSeed<'de> for RawVariantSeed<S> {
+    type Value = (S::Value, bool);
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Self::Value, D::Error> {
+        enum Tag {
+            Name(String),
+            Index(u64),
+        }
+
+        struct TagVisitor;
+
+        impl<'de> de::Visitor<'de> for TagVisitor {
+            type Value = Tag;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a variant name or index")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Tag, E> {
+                Ok(Tag::Name(v.to_owned()))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> std::result::Result<Tag, E> {
+                Ok(Tag::Name(v))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<Tag, E> {
+                Ok(Tag::Index(v))
+            }
+        }
+
+        match deserializer.deserialize_identifier(TagVisitor)? {
+            Tag::Name(name) if self.variants.contains(&name.as_str()) => Ok((
+                self.seed
+                    .deserialize(de::value::StringDeserializer::<D::Error>::new(name))?,
+                false,
+            )),
+            Tag::Index(index) if index < self.variants.len() as u64 => Ok((
+                self.seed
+                    .deserialize(de::value::U64Deserializer::<D::Error>::new(index))?,
+                false,
+            )),
+            _ => Ok((
+                self.seed
+                    .deserialize(de::value::StrDeserializer::<D::Error>::new(self.name))?,
+                true,
+            )),
+        }
+    }
+}
+
+/// Deserializes the key of a `{ Variant: ...payload... }` object, which might also hold
+/// the variant index as produced by `variant_keys_as_index`, or the variant name converted
+/// with `variant_case` or in a different case with `case_insensitive_variants`.
+struct VariantKeyDeserializer {
+    key: String,
+    variants: &'static [&'static str],
+    config: Rc<Config>,
+}
+
+impl<'de> de::Deserializer<'de> for VariantKeyDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if let Some(variant) = self.config.find_variant(&self.key, self.variants) {
+            return visitor.visit_borrowed_str(variant);
+        }
+        // Variants explicitly renamed to numbers take priority over indices.
+        if !self.variants.contains(&self.key.as_str()) {
+            if let Ok(index) = self.key.parse::<u32>() {
+                if index.to_string() == self.key {
+                    return visitor.visit_u32(index);
+                }
+            }
+        }
+        visitor.visit_string(self.key)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Collects `[key, value]` pairs of enumerable string-keyed properties of an object, including
+/// inherited ones, in the same order as a `for...in` loop would visit them.
+fn entries_with_inherited(obj: &Object) -> Array {
+    let entries = Array::new();
+    // Own properties, even non-enumerable ones, shadow the inherited ones with the same key.
+    let seen = js_sys::Set::new(&JsValue::UNDEFINED);
+    let mut current = obj.clone();
+    while !current.is_null() {
+        for key in Object::get_own_property_names(&current).iter() {
+            if seen.has(&key) {
+                continue;
+            }
+            seen.add(&key);
+            if current.property_is_enumerable(&key) {
+                let value = obj.unchecked_ref::<ObjectExt>().get(key.unchecked_ref());
+                entries.push(&Array::of2(&key, &value));
+            }
+        }
+        current = Object::get_prototype_of(&current);
+    }
+    entries
+}
+
+/// A user-provided function picking an enum variant name for a raw JS value.
+type Discriminator = dyn Fn(&JsValue) -> Option<&'static str>;
+
+/// Deserializer options shared by a [`Deserializer`] with all the nested ones it creates.
+#[derive(Clone, Default)]
+struct Config {
+    ignored_variant_key_prefixes: &'static [&'static str],
+    enum_discriminator: Option<Rc<Discriminator>>,
+    struct_variant_tag: Option<&'static str>,
+    adjacent_variant_tag: Option<(&'static str, &'static str)>,
+    indexed_variant_tag: Option<(&'static str, &'static str)>,
+    variant_case: Case,
+    case_insensitive_variants: bool,
+    raw_variant: Option<&'static str>,
+    include_inherited_properties: bool,
+}
+
+impl Config {
+    /// Finds the original variant name for one converted with `variant_case`, or differing
+    /// from it only in ASCII case with `case_insensitive_variants`.
+    fn find_variant(&self, name: &str, variants: &'static [&'static str]) -> Option<&'static str> {
+        if let Some(variant) = self.variant_case.find_variant(name, variants) {
+            return Some(variant);
+        }
+        if self.case_insensitive_variants && !variants.contains(&name) {
+            return variants
+                .iter()
+                .copied()
+                .find(|variant| variant.eq_ignore_ascii_case(name));
+        }
+        None
     }
 }
 
 /// A newtype that allows using any [`JsValue`] as a [`serde::Deserializer`].
 pub struct Deserializer {
     value: JsValue,
+    config: Rc<Config>,
+    /// Only set for the top-level value in [`crate::try_from_value`].
+    field_errors: Option<Rc<FieldErrors>>,
+    /// Only set for the tag keys of a flattened struct variant, which aren't its fields.
+    skipped_keys: Vec<&'static str>,
+    /// Only set for the top-level value in [`crate::from_value_tracking_presence`].
+    present_fields: Option<Rc<PresentFields>>,
 }
 
 impl From<JsValue> for Deserializer {
     fn from(value: JsValue) -> Self {
-        Self { value }
+        Self {
+            value,
+            config: Default::default(),
+            field_errors: None,
+            skipped_keys: Vec::new(),
+            present_fields: None,
+        }
     }
 }
 
 /// Destructures a JS `[key, value]` pair into a tuple of [`Deserializer`]s.
-fn convert_pair(pair: JsValue) -> (Deserializer, Deserializer) {
+fn convert_pair(pair: JsValue, config: &Rc<Config>) -> (Deserializer, Deserializer) {
     let pair = pair.unchecked_into::<Array>();
-    (pair.get(0).into(), pair.get(1).into())
+    (
+        Deserializer::with_config(pair.get(0), config),
+        Deserializer::with_config(pair.get(1), config),
+    )
 }
 
 impl Deserializer {
+    /// Ignores object keys starting with any of the given prefixes (e.g. `&["$", "_"]`) when
+    /// looking for the variant key of an externally tagged enum. This allows deserializing
+    /// enums from objects decorated with framework metadata like `{ $meta: ..., Variant: ... }`.
+    /// Empty by default.
+    #[must_use]
+    pub fn ignore_variant_key_prefixes(mut self, prefixes: &'static [&'static str]) -> Self {
+        Rc::make_mut(&mut self.config).ignored_variant_key_prefixes = prefixes;
+        self
+    }
+
+    /// Sets a function that inspects a raw JS value an enum is deserialized from and returns the
+    /// name of the variant to use, or `None` to fall back to the default representation.
+    ///
+    /// When a variant is picked this way, the whole value becomes its payload, so e.g. objects
+    /// can be routed to different struct variants based on presence of a specific field.
+    /// The function is called for every enum, including nested ones.
+    #[must_use]
+    pub fn enum_discriminator(
+        mut self,
+        discriminator: impl Fn(&JsValue) -> Option<&'static str> + 'static,
+    ) -> Self {
+        Rc::make_mut(&mut self.config).enum_discriminator = Some(Rc::new(discriminator));
+        self
+    }
+
+    /// Set to `true` to also read enumerable properties inherited from the prototype chain, in
+    /// the same order as a `for...in` loop, when deserializing maps and other values from plain
+    /// objects. Only own properties are read by default, like `Object.entries` does, so that
+    /// anything added to shared prototypes doesn't leak into the data. `false` by default.
+    ///
+    /// Struct fields are looked up by name, so they are found on the prototype chain regardless.
+    #[must_use]
+    pub fn include_inherited_properties(mut self, value: bool) -> Self {
+        Rc::make_mut(&mut self.config).include_inherited_properties = value;
+        self
+    }
+
+    /// Also accepts enums from objects with a tag field holding the variant name alongside
+    /// the variant fields, e.g. `{ type: "Point", x, y }` with `Some("type")`, as produced by
+    /// [`Serializer::struct_variant_tag`](crate::Serializer::struct_variant_tag).
+    /// `None` by default.
+    #[must_use]
+    pub fn struct_variant_tag(mut self, tag: Option<&'static str>) -> Self {
+        Rc::make_mut(&mut self.config).struct_variant_tag = tag;
+        self
+    }
+
+    /// Also accepts enums from objects with a tag field holding the variant name and a content
+    /// field holding the payload, e.g. `{ __typename: "Point", data: { x, y } }` with
+    /// `Some(("__typename", "data"))`, as produced by
+    /// [`Serializer::adjacent_variant_tag`](crate::Serializer::adjacent_variant_tag).
+    /// The content field can be omitted for unit variants. `None` by default.
+    #[must_use]
+    pub fn adjacent_variant_tag(mut self, keys: Option<(&'static str, &'static str)>) -> Self {
+        Rc::make_mut(&mut self.config).adjacent_variant_tag = keys;
+        self
+    }
+
+    /// Also accepts enums from objects with a tag field holding the variant name and an index
+    /// field holding the variant index alongside the variant fields, e.g.
+    /// `{ kind: "Point", index: 2, x, y }` with `Some(("kind", "index"))`, as produced by
+    /// [`Serializer::indexed_variant_tag`](crate::Serializer::indexed_variant_tag).
+    /// The index takes precedence if both are present, but either one is enough. Unit variants
+    /// are accepted from objects without any fields. `None` by default.
+    #[must_use]
+    pub fn indexed_variant_tag(mut self, keys: Option<(&'static str, &'static str)>) -> Self {
+        Rc::make_mut(&mut self.config).indexed_variant_tag = keys;
+        self
+    }
+
+    /// Also accepts enum variant names converted to the given case convention, e.g.
+    /// `IN_PROGRESS` for `InProgress` with [`Case::ScreamingSnake`], as produced by
+    /// [`Serializer::variant_case`](crate::Serializer::variant_case).
+    /// [`Case::Unchanged`] by default.
+    #[must_use]
+    pub fn variant_case(mut self, case: Case) -> Self {
+        Rc::make_mut(&mut self.config).variant_case = case;
+        self
+    }
+
+    /// Set to `true` to accept enum variant names that differ only in ASCII case, e.g. `"circle"`
+    /// or `"CIRCLE"` for `Circle`. Exact matches still take precedence. `false` by default.
+    ///
+    /// This applies wherever the deserializer reads variant names, including the tag field of
+    /// [`Self::struct_variant_tag`]. Tags of enums with `#[serde(tag = "...")]` aren't covered:
+    /// serde reads them as a plain identifier, without telling the deserializer the variant
+    /// names, and gives it a single attempt at matching one, so there is nothing to compare the
+    /// tag against. Instead, either leave such enums externally tagged and set
+    /// [`Self::struct_variant_tag`] to the tag name, or list the accepted spellings on the
+    /// variants themselves via `#[serde(alias = "circle")]`.
+    #[must_use]
+    pub fn case_insensitive_variants(mut self, value: bool) -> Self {
+        Rc::make_mut(&mut self.config).case_insensitive_variants = value;
+        self
+    }
+
+    /// Set to a variant name like `Some("Raw")` to deserialize enums that have a newtype
+    /// variant with this name from values holding any variant that they don't have.
+    /// The raw variant gets the whole original value as its payload, so that it can be kept
+    /// as a [`PreservedValue`](crate::PreservedValue) and written back verbatim by
+    /// [`Serializer::raw_variant`](crate::Serializer::raw_variant). `None` by default.
+    ///
+    /// Only unknown variant names and indices are redirected; invalid payloads of known
+    /// variants and values that don't look like enums at all are still errors.
+    #[must_use]
+    pub fn raw_variant(mut self, variant: Option<&'static str>) -> Self {
+        Rc::make_mut(&mut self.config).raw_variant = variant;
+        self
+    }
+
+    /// Deserializes an object with an `Array` of values per field, e.g.
+    /// `{ id: [1, 2], name: ["a", "b"] }`, into a `Vec` with an element per row, as produced by
+    /// [`Serializer::serialize_columnar`](crate::Serializer::serialize_columnar).
+    ///
+    /// Each row is deserialized like an object with the column names as keys, holding the values
+    /// at the row index, so missing columns are handled as missing fields. All columns must have
+    /// the same length. An object without columns produces no rows.
+    pub fn deserialize_columnar<T: de::DeserializeOwned>(self) -> Result<Vec<T>> {
+        if !self.value.is_object() || Array::is_array(&self.value) {
+            return Err(self.invalid_type_(&"an object of columns"));
+        }
+        let mut columns = Vec::<(String, Array)>::new();
+        for entry in Object::entries(self.value.unchecked_ref()).iter() {
+            let (name, values) = convert_pair(entry, &self.config);
+            // Keys of `Object.entries` are always strings.
+            let name = name.value.as_string().unwrap_or_default();
+            if !Array::is_array(&values.value) {
+                return Err(de::Error::custom(format_args!(
+                    "column `{}` is not an array",
+                    name
+                )));
+            }
+            let values = values.value.unchecked_into::<Array>();
+            if let Some((first_name, first_values)) = columns.first() {
+                if values.length() != first_values.length() {
+                    return Err(de::Error::custom(format_args!(
+                        "column `{}` has {} values, but column `{}` has {}",
+                        name,
+                        values.length(),
+                        first_name,
+                        first_values.length()
+                    )));
+                }
+            }
+            columns.push((name, values));
+        }
+        let rows = columns.first().map_or(0, |(_, values)| values.length());
+        let columns = Columns {
+            columns,
+            config: self.config,
+        };
+        (0..rows)
+            .map(|row| {
+                T::deserialize(ColumnRow {
+                    columns: &columns,
+                    row,
+                })
+            })
+            .collect()
+    }
+
+    fn with_config(value: JsValue, config: &Rc<Config>) -> Self {
+        Self {
+            value,
+            config: config.clone(),
+            field_errors: None,
+            skipped_keys: Vec::new(),
+            present_fields: None,
+        }
+    }
+
+    pub(crate) fn with_field_errors(value: JsValue, field_errors: &Rc<FieldErrors>) -> Self {
+        Self {
+            field_errors: Some(field_errors.clone()),
+            ..Self::from(value)
+        }
+    }
+
+    pub(crate) fn with_present_fields(value: JsValue, present_fields: &Rc<PresentFields>) -> Self {
+        Self {
+            present_fields: Some(present_fields.clone()),
+            ..Self::from(value)
+        }
+    }
+
+    /// Replaces a variant name string converted with [`Self::variant_case`] or in a different case
+    /// with [`Self::case_insensitive_variants`] with the original one.
+    fn with_original_variant(mut self, variants: &'static [&'static str]) -> Self {
+        if self.config.variant_case != Case::Unchanged || self.config.case_insensitive_variants {
+            if let Some(variant) = self
+                .value
+                .as_string()
+                .and_then(|name| self.config.find_variant(&name, variants))
+            {
+                self.value = JsString::clone(&static_str_to_js(variant)).into();
+            }
+        }
+        self
+    }
+
     /// Casts the internal value into an object, including support for prototype-less objects.
     /// See https://github.com/rustwasm/wasm-bindgen/issues/1366 for why we don't use `dyn_ref`.
     fn as_object_entries(&self) -> Option<Array> {
-        if self.value.is_object() {
-            Some(Object::entries(self.value.unchecked_ref()))
-        } else {
+        if !self.value.is_object() {
             None
+        } else if self.config.include_inherited_properties {
+            Some(entries_with_inherited(self.value.unchecked_ref()))
+        } else {
+            Some(Object::entries(self.value.unchecked_ref()))
+        }
+    }
+
+    /// Checks whether the internal value is a non-iterable object created by an object literal,
+    /// `JSON.parse` or `Object.create(null)`, as opposed to an instance of some class.
+    fn is_plain_object(&self) -> bool {
+        thread_local! {
+            static OBJECT_PROTOTYPE: Object = Object::get_prototype_of(&Object::new());
         }
+        if !self.value.is_object() {
+            return false;
+        }
+        let prototype = Object::get_prototype_of(&self.value);
+        (prototype.is_null()
+            || OBJECT_PROTOTYPE.with(|object_prototype| prototype == *object_prototype))
+            && !Reflect::has(&self.value, &Symbol::iterator()).unwrap_or(false)
     }
 
     fn is_nullish(&self) -> bool {
@@ -169,10 +705,19 @@ impl Deserializer {
         } else if let Some(v) = self.value.dyn_ref::<ArrayBuffer>() {
             temp = Uint8Array::new(v);
             &temp
+        } else if let Some(v) = self.value.dyn_ref::<DataView>() {
+            temp = Uint8Array::new_with_byte_offset_and_length(
+                &v.buffer(),
+                v.byte_offset() as u32,
+                v.byte_length() as u32,
+            );
+            &temp
         } else {
             return None;
         };
 
+        // Allocates a `Vec` of the final size and copies the bytes in bulk, so even very large
+        // buffers don't cause reallocations or temporary copies on the Rust side.
         Some(v.to_vec())
     }
 
@@ -206,31 +751,62 @@ impl Deserializer {
     }
 
     fn as_safe_integer(&self) -> Option<i64> {
-        if let Some(v) = self.value.as_f64() {
-            if Number::is_safe_integer(&self.value) {
-                return Some(v as i64);
-            }
+        match self.value.as_f64() {
+            Some(v) if is_safe_integer(v) => Some(v as i64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an integer if it's a JS number, or an error naming the target type
+    /// `ty` if the number has a fractional part or is outside of `min..=max` or the range of
+    /// safe integers. Returns `None` for anything but numbers.
+    fn as_integer(&self, ty: &str, min: f64, max: f64) -> Result<Option<i64>> {
+        let v = match self.value.as_f64() {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        if v.trunc() != v {
+            Err(de::Error::custom(format_args!(
+                "invalid value: {} is not an integer, expected {}",
+                v, ty
+            )))
+        } else if !(min..=max).contains(&v) {
+            Err(de::Error::custom(format_args!(
+                "invalid value: {} is out of range for {}",
+                v, ty
+            )))
+        } else if !is_safe_integer(v) {
+            Err(de::Error::custom(format_args!(
+                "invalid value: {} is not a safe integer, expected {}",
+                v, ty
+            )))
+        } else {
+            Ok(Some(v as i64))
         }
-        None
     }
 
     fn deserialize_from_js_number_signed<'de, V: de::Visitor<'de>>(
         &self,
         visitor: V,
+        ty: &str,
+        min: f64,
+        max: f64,
     ) -> Result<V::Value> {
-        match self.as_safe_integer() {
+        match self.as_integer(ty, min, max)? {
             Some(v) => visitor.visit_i64(v),
-            _ => self.invalid_type(visitor),
+            None => self.invalid_type(visitor),
         }
     }
 
     fn deserialize_from_js_number_unsigned<'de, V: de::Visitor<'de>>(
         &self,
         visitor: V,
+        ty: &str,
+        max: f64,
     ) -> Result<V::Value> {
-        match self.as_safe_integer() {
-            Some(v) if v >= 0 => visitor.visit_u64(v as _),
-            _ => self.invalid_type(visitor),
+        match self.as_integer(ty, 0.0, max)? {
+            Some(v) => visitor.visit_u64(v as _),
+            None => self.invalid_type(visitor),
         }
     }
 }
@@ -247,7 +823,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         } else if let Some(v) = self.value.as_bool() {
             visitor.visit_bool(v)
         } else if let Some(v) = self.value.as_f64() {
-            if Number::is_safe_integer(&self.value) {
+            if is_safe_integer(v) {
                 visitor.visit_i64(v as i64)
             } else {
                 visitor.visit_f64(v)
@@ -256,20 +832,39 @@ impl<'de> de::Deserializer<'de> for Deserializer {
             visitor.visit_string(v)
         } else if Array::is_array(&self.value) {
             self.deserialize_seq(visitor)
-        } else if self.value.is_object() &&
-            // The only reason we want to support objects here is because serde uses
-            // `deserialize_any` for internally tagged enums
+        } else if self.is_plain_object() {
+            // The most common case, so it's handled before probing for other kinds of objects.
+            self.deserialize_map(visitor)
+        } else if let Some(bytes) = self.as_bytes() {
+            // Self-describing formats like `serde_json::Value` usually don't support byte buffers,
+            // so provide them as a sequence of numbers, which is what they serialize to as well.
+            de::Deserializer::deserialize_any(
+                de::value::SeqDeserializer::<_, Error>::new(bytes.into_iter()),
+                visitor,
+            )
+        } else if self.value.is_instance_of::<Map>()
+            || (self.value.is_object() &&
+            // Other than for generic targets like `serde_json::Value`, we want to support objects
+            // here because serde uses `deserialize_any` for internally tagged enums
             // (see https://github.com/cloudflare/serde-wasm-bindgen/pull/4#discussion_r352245020).
             //
-            // We expect such enums to be represented via plain JS objects, so let's explicitly
-            // exclude Sets, Maps and any other iterables. These should be deserialized via concrete
-            // `deserialize_*` methods instead of us trying to guess the right target type.
+            // We expect such enums to be represented via plain JS objects or Maps, so let's
+            // explicitly exclude Sets and any other iterables. These should be deserialized via
+            // concrete `deserialize_*` methods instead of us trying to guess the right target type.
             //
             // Hopefully we can rid of these hacks altogether once
             // https://github.com/serde-rs/serde/issues/1183 is implemented / fixed on serde side.
-            !Reflect::has(&self.value, &Symbol::iterator()).unwrap_or(false)
+            !Reflect::has(&self.value, &Symbol::iterator()).unwrap_or(false))
         {
             self.deserialize_map(visitor)
+        } else if let Some(bigint) = self.value.dyn_ref::<BigInt>() {
+            // Prefer `u64` for non-negative values, and let `i64` handle the rest with its range checks.
+            let converted_number = bindings::bigint_to_u64(bigint);
+            if &bindings::bigint_from_u64(converted_number) == bigint {
+                visitor.visit_u64(converted_number)
+            } else {
+                self.deserialize_i64(visitor)
+            }
         } else {
             self.invalid_type(visitor)
         }
@@ -291,11 +886,21 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         self.deserialize_unit(visitor)
     }
 
+    /// Supported inputs:
+    ///  - A JS boolean.
+    ///  - `0` or `1`, as produced by `bools_as_numbers`.
     fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         if let Some(v) = self.value.as_bool() {
-            visitor.visit_bool(v)
-        } else {
-            self.invalid_type(visitor)
+            return visitor.visit_bool(v);
+        }
+        match self.value.as_f64() {
+            Some(0.0) => visitor.visit_bool(false),
+            Some(1.0) => visitor.visit_bool(true),
+            Some(v) => Err(de::Error::invalid_value(
+                de::Unexpected::Float(v),
+                &"a boolean, 0 or 1",
+            )),
+            None => self.invalid_type(visitor),
         }
     }
 
@@ -329,49 +934,50 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     }
 
     // Serde happily converts any integer to any integer (with checks), so let's forward all of
-    // these to 64-bit methods to save some space in the generated WASM.
+    // these to 64-bit methods to save some space in the generated WASM. The range is still
+    // checked upfront though, so that errors can name the JS number and the target type.
 
     fn deserialize_i8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        self.deserialize_from_js_number_signed(visitor)
+        self.deserialize_from_js_number_signed(visitor, "i8", i8::MIN.into(), i8::MAX.into())
     }
 
     fn deserialize_i16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        self.deserialize_from_js_number_signed(visitor)
+        self.deserialize_from_js_number_signed(visitor, "i16", i16::MIN.into(), i16::MAX.into())
     }
 
     fn deserialize_i32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        self.deserialize_from_js_number_signed(visitor)
+        self.deserialize_from_js_number_signed(visitor, "i32", i32::MIN.into(), i32::MAX.into())
     }
 
     // TODO: Add i128 deserializer rather than forwarding to i64
-    serde_if_integer128! {
-        fn deserialize_i128<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-            self.deserialize_i64(visitor)
-        }
+    fn deserialize_i128<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_i64(visitor)
     }
 
     // Same as above, but for `i64`.
 
     fn deserialize_u8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        self.deserialize_from_js_number_unsigned(visitor)
+        self.deserialize_from_js_number_unsigned(visitor, "u8", u8::MAX.into())
     }
 
     fn deserialize_u16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        self.deserialize_from_js_number_unsigned(visitor)
+        self.deserialize_from_js_number_unsigned(visitor, "u16", u16::MAX.into())
     }
 
     fn deserialize_u32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        self.deserialize_from_js_number_unsigned(visitor)
+        self.deserialize_from_js_number_unsigned(visitor, "u32", u32::MAX.into())
     }
 
     // TODO: Add u128 deserializer rather than forwarding to u64
-    serde_if_integer128! {
-        fn deserialize_u128<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-            self.deserialize_u64(visitor)
-        }
+    fn deserialize_u128<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_u64(visitor)
     }
 
     fn deserialize_i64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        // Plain numbers are far more common than `BigInt`s, so check for them first.
+        if let Some(v) = self.as_integer("i64", i64::MIN as f64, i64::MAX as f64)? {
+            return visitor.visit_i64(v);
+        }
         if let Some(bigint) = self.value.dyn_ref::<BigInt>() {
             let converted_number = bindings::bigint_to_i64(bigint);
             // Do a round trip check in order to make sure that no information was lost
@@ -383,11 +989,14 @@ impl<'de> de::Deserializer<'de> for Deserializer {
                 ))
             }
         } else {
-            self.deserialize_from_js_number_signed(visitor)
+            self.invalid_type(visitor)
         }
     }
 
     fn deserialize_u64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if let Some(v) = self.as_integer("u64", 0.0, u64::MAX as f64)? {
+            return visitor.visit_u64(v as _);
+        }
         if let Some(bigint) = self.value.dyn_ref::<BigInt>() {
             let converted_number = bindings::bigint_to_u64(bigint);
             // Do a round trip check in order to make sure that no information was lost
@@ -399,7 +1008,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
                 ))
             }
         } else {
-            self.deserialize_from_js_number_unsigned(visitor)
+            self.invalid_type(visitor)
         }
     }
 
@@ -408,11 +1017,27 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     /// By default we don't perform detection of single chars because it's pretty complicated,
     /// but if we get a hint that they're expected, this methods allows to avoid heap allocations
     /// of an intermediate `String` by directly converting numeric codepoints instead.
+    ///
+    /// Numeric code points, as produced by `serialize_char_as_number`, are accepted as well.
     fn deserialize_char<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         if let Some(s) = self.value.dyn_ref::<JsString>() {
             if let Some(c) = s.as_char() {
                 return visitor.visit_char(c);
             }
+        } else if let Some(v) = self.value.as_f64() {
+            // Surrogates and values outside of the Unicode range are rejected by `char::from_u32`.
+            let c = if v.fract() == 0.0 && (0.0..=f64::from(u32::MAX)).contains(&v) {
+                char::from_u32(v as u32)
+            } else {
+                None
+            };
+            return match c {
+                Some(c) => visitor.visit_char(c),
+                None => Err(de::Error::invalid_value(
+                    de::Unexpected::Float(v),
+                    &"a Unicode scalar value",
+                )),
+            };
         }
         self.invalid_type(visitor)
     }
@@ -427,17 +1052,22 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         }
     }
 
-    /// Simply calls `visit_newtype_struct`.
+    /// Simply calls `visit_newtype_struct`, except for [`PreservedValue`](crate::PreservedValue)
+    /// which takes the value as-is.
     fn deserialize_newtype_struct<V: de::Visitor<'de>>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value> {
+        if name == PRESERVED_VALUE_MAGIC {
+            return with_handover(self.value, || visitor.visit_unit());
+        }
         visitor.visit_newtype_struct(self)
     }
 
     /// Supported inputs:
     ///  - JS iterable (an object with [`[Symbol.iterator]`](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Symbol/iterator)).
+    ///
     /// Supported outputs:
     ///  - Any Rust sequence from Serde point of view ([`Vec`], [`HashSet`](std::collections::HashSet), etc.)
     fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
@@ -448,7 +1078,10 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         } else {
             return self.invalid_type(visitor);
         };
-        visitor.visit_seq(SeqAccess { iter })
+        visitor.visit_seq(SeqAccess {
+            iter,
+            config: self.config,
+        })
     }
 
     /// Forwards to [`Self::deserialize_seq`](#method.deserialize_seq).
@@ -469,25 +1102,59 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     /// Supported inputs:
     ///  - A JS iterable that is expected to return `[key, value]` pairs.
     ///  - A JS object, which will be iterated using [`Object.entries`](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/entries).
+    ///
+    /// Entries are visited in their JS iteration order, which is the insertion order except for
+    /// integer-like object keys that JS always lists first.
+    ///
     /// Supported outputs:
     ///  - A Rust key-value map ([`HashMap`](std::collections::HashMap), [`BTreeMap`](std::collections::BTreeMap), etc.).
     ///  - A typed Rust structure with `#[derive(Deserialize)]`.
     fn deserialize_map<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let mut check_pairs = false;
         let map = MapAccess {
             iter: match js_sys::try_iter(&self.value)? {
-                Some(iter) => iter,
-                None => match self.as_object_entries() {
-                    Some(entries) => entries.values().into_iter(),
-                    None => return self.invalid_type(visitor),
-                },
+                Some(iter) if self.skipped_keys.is_empty() => {
+                    // Other iterables, like arrays of entries, can produce anything.
+                    check_pairs = !self.value.is_instance_of::<Map>();
+                    iter
+                }
+                iter => {
+                    let mut entries = match iter {
+                        Some(_) => Array::from(&self.value),
+                        None => match self.as_object_entries() {
+                            Some(entries) => entries,
+                            None => return self.invalid_type(visitor),
+                        },
+                    };
+                    // Entries of flattened struct variants are collected to leave out the tag.
+                    if !self.skipped_keys.is_empty() {
+                        let keys: Vec<_> = self
+                            .skipped_keys
+                            .iter()
+                            .map(|&key| static_str_to_js(key))
+                            .collect();
+                        entries = entries.filter(&mut |entry, _, _| {
+                            let entry_key = Array::from(&entry).get(0);
+                            keys.iter().all(|key| {
+                                let key: &JsValue = key;
+                                entry_key != *key
+                            })
+                        });
+                    }
+                    entries.values().into_iter()
+                }
             },
             next_value: None,
+            config: self.config,
+            check_pairs,
         };
         visitor.visit_map(map)
     }
 
     /// Supported inputs:
     ///  - A plain JS object.
+    ///  - An ES2015 `Map` with string keys, which are looked up by field name like object keys.
+    ///
     /// Supported outputs:
     ///  - A typed Rust structure with `#[derive(Deserialize)]`.
     fn deserialize_struct<V: de::Visitor<'de>>(
@@ -496,36 +1163,178 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
-        let obj = if self.value.is_object() {
-            self.value.unchecked_into::<ObjectExt>()
+        // Fields of `Map`s are looked up the same way as of objects, so that they are tracked
+        // by `try_from_value` and `from_value_tracking_presence` too.
+        let obj = if self.value.is_instance_of::<Map>() {
+            FieldSource::Map(self.value.unchecked_into())
+        } else if self.value.is_object() {
+            FieldSource::Object(self.value.unchecked_into())
         } else {
             return self.invalid_type(visitor);
         };
-        let map = ObjectAccess { obj, fields };
+        let map = ObjectAccess {
+            obj,
+            fields,
+            config: self.config,
+            field_errors: self.field_errors,
+            skipped_keys: self.skipped_keys,
+            present_fields: self.present_fields,
+        };
         visitor.visit_map(map)
     }
 
     /// Here we try to be compatible with `serde-json`, which means supporting:
     ///  - `"Variant"` - gets converted to a unit variant `MyEnum::Variant`
+    ///  - `0`, `1`, ... - a unit variant by its index, as produced by `unit_variants_as_index`.
     ///  - `{ Variant: ...payload... }` - gets converted to a `MyEnum::Variant { ...payload... }`.
+    ///  - `{ 0: ...payload... }` - same by the variant index, as produced by `variant_keys_as_index`.
+    ///  - `new Map([["Variant", ...payload...]])` - same as a `Map`, as produced by `variant_wrappers_as_maps`.
+    ///
+    /// Keys matching [`Self::ignore_variant_key_prefixes`] are skipped when looking for the variant,
+    /// and variant names converted with [`Self::variant_case`] are accepted as well.
+    /// Both representations can be overridden via [`Self::enum_discriminator`], objects
+    /// carrying a [`Self::adjacent_variant_tag`] are read as the variant and its payload, and
+    /// objects carrying a [`Self::indexed_variant_tag`] or a [`Self::struct_variant_tag`] are read
+    /// as the variant fields themselves.
     fn deserialize_enum<V: de::Visitor<'de>>(
         self,
         _name: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
+        let raw = self
+            .config
+            .raw_variant
+            .filter(|raw| variants.contains(raw))
+            .map(|name| RawVariant {
+                name,
+                variants,
+                value: self.value.clone(),
+            });
+        if let Some(discriminator) = &self.config.enum_discriminator {
+            if let Some(variant) = discriminator(&self.value) {
+                let access = EnumAccess {
+                    raw,
+                    tag: Deserializer::with_config(
+                        JsString::clone(&static_str_to_js(variant)).into(),
+                        &self.config,
+                    ),
+                    payload: self,
+                };
+                return visitor.visit_enum(access);
+            }
+        }
+        if let Some((tag, content)) = self.config.adjacent_variant_tag {
+            if self.value.is_object() {
+                let obj = self.value.unchecked_ref::<ObjectExt>();
+                if let Some(key) = obj.get(&static_str_to_js(tag)).as_string() {
+                    let payload = obj.get(&static_str_to_js(content));
+                    return visitor.visit_enum(EnumAccess {
+                        raw,
+                        tag: VariantKeyDeserializer {
+                            key,
+                            variants,
+                            config: self.config.clone(),
+                        },
+                        payload: Deserializer::with_config(payload, &self.config),
+                    });
+                }
+            }
+        }
+        if let Some((tag, index)) = self.config.indexed_variant_tag {
+            if self.value.is_object() {
+                let obj = self.value.unchecked_ref::<ObjectExt>();
+                let variant_index = Deserializer::from(obj.get(&static_str_to_js(index)))
+                    .as_safe_integer()
+                    .filter(|&index| index >= 0);
+                let variant = obj.get(&static_str_to_js(tag));
+                if variant_index.is_some() || variant.is_string() {
+                    let payload = Deserializer {
+                        skipped_keys: vec![tag, index],
+                        ..self
+                    };
+                    // Matching by index is cheaper, so the name is only used without one.
+                    return match variant_index {
+                        Some(index) => visitor.visit_enum(EnumAccess {
+                            raw,
+                            tag: de::value::U64Deserializer::new(index as u64),
+                            payload,
+                        }),
+                        None => visitor.visit_enum(EnumAccess {
+                            raw,
+                            tag: Deserializer::with_config(variant, &payload.config)
+                                .with_original_variant(variants),
+                            payload,
+                        }),
+                    };
+                }
+            }
+        }
+        if let Some(tag) = self.config.struct_variant_tag {
+            if self.value.is_object() {
+                let variant = self
+                    .value
+                    .unchecked_ref::<ObjectExt>()
+                    .get(&static_str_to_js(tag));
+                if variant.is_string() {
+                    return visitor.visit_enum(EnumAccess {
+                        raw,
+                        tag: Deserializer::with_config(variant, &self.config)
+                            .with_original_variant(variants),
+                        payload: Deserializer {
+                            skipped_keys: vec![tag],
+                            ..self
+                        },
+                    });
+                }
+            }
+        }
         let access = if self.value.is_string() {
             EnumAccess {
-                tag: self.value.into(),
-                payload: JsValue::UNDEFINED.into(),
+                raw,
+                payload: Deserializer::with_config(JsValue::UNDEFINED, &self.config),
+                tag: self.with_original_variant(variants),
+            }
+        } else if self.value.as_f64().is_some() {
+            let index = match self.as_safe_integer() {
+                Some(index) if index >= 0 => index as u64,
+                _ => return self.invalid_type(visitor),
+            };
+            return visitor.visit_enum(EnumAccess {
+                raw,
+                tag: de::value::U64Deserializer::new(index),
+                payload: Deserializer::with_config(JsValue::UNDEFINED, &self.config),
+            });
+        } else if let Some(mut entries) = match self.value.dyn_ref::<Map>() {
+            Some(map) => Some(Array::from(&map.entries())),
+            None => self.as_object_entries(),
+        } {
+            let prefixes = self.config.ignored_variant_key_prefixes;
+            if !prefixes.is_empty() {
+                entries = entries.filter(&mut |entry, _, _| {
+                    // `Map` keys aren't necessarily strings.
+                    Array::from(&entry)
+                        .get(0)
+                        .as_string()
+                        .is_none_or(|key| !prefixes.iter().any(|prefix| key.starts_with(prefix)))
+                });
             }
-        } else if let Some(entries) = self.as_object_entries() {
             if entries.length() != 1 {
                 return Err(de::Error::invalid_length(entries.length() as _, &"1"));
             }
-            let entry = entries.get(0);
-            let (tag, payload) = convert_pair(entry);
-            EnumAccess { tag, payload }
+            let (key, payload) = convert_pair(entries.get(0), &self.config);
+            return match key.value.as_string() {
+                Some(key) => visitor.visit_enum(EnumAccess {
+                    raw,
+                    tag: VariantKeyDeserializer {
+                        key,
+                        variants,
+                        config: self.config.clone(),
+                    },
+                    payload,
+                }),
+                None => key.invalid_type(visitor),
+            };
         } else {
             return self.invalid_type(visitor);
         };
@@ -547,6 +1356,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     ///
     /// Supported inputs:
     ///  - `ArrayBuffer` - converted to an `Uint8Array` view first.
+    ///  - `DataView` - converted to an `Uint8Array` view over the same bytes first.
     ///  - `Uint8Array` - copied to a newly created `Vec<u8>` on the Rust side.
     fn deserialize_byte_buf<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         if let Some(bytes) = self.as_bytes() {
@@ -564,7 +1374,11 @@ impl<'de> de::Deserializer<'de> for Deserializer {
 impl<'de> de::VariantAccess<'de> for Deserializer {
     type Error = Error;
 
+    /// Objects holding just the tags of a flattened variant are accepted as unit variants.
     fn unit_variant(self) -> Result<()> {
+        if !self.skipped_keys.is_empty() {
+            return Ok(());
+        }
         de::Deserialize::deserialize(self)
     }
 
@@ -584,3 +1398,71 @@ impl<'de> de::VariantAccess<'de> for Deserializer {
         de::Deserializer::deserialize_struct(self, "", fields, visitor)
     }
 }
+
+/// Columns read by [`Deserializer::deserialize_columnar`], along with their names.
+struct Columns {
+    columns: Vec<(String, Array)>,
+    config: Rc<Config>,
+}
+
+/// Deserializes a single row of [`Columns`] as a map from column names to values in this row.
+struct ColumnRow<'a> {
+    columns: &'a Columns,
+    row: u32,
+}
+
+impl<'de> de::Deserializer<'de> for ColumnRow<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_map(ColumnRowAccess {
+            columns: self.columns,
+            row: self.row,
+            next: 0,
+        })
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct ColumnRowAccess<'a> {
+    columns: &'a Columns,
+    row: u32,
+    next: usize,
+}
+
+impl<'de> de::MapAccess<'de> for ColumnRowAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        Ok(match self.columns.columns.get(self.next) {
+            Some((name, _)) => Some(seed.deserialize(str_deserializer(name))?),
+            None => None,
+        })
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let (_, values) = &self.columns.columns[self.next];
+        self.next += 1;
+        seed.deserialize(Deserializer::with_config(
+            values.get(self.row),
+            &self.columns.config,
+        ))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.columns.columns.len() - self.next)
+    }
+}
@@ -0,0 +1,54 @@
+//! Helpers shared by the [`duration_secs`](crate::duration_secs) and
+//! [`duration_millis`](crate::duration_millis) with-modules.
+
+use serde::ser;
+use std::time::Duration;
+
+pub(crate) const MAX_SAFE_INTEGER: u64 = 9_007_199_254_740_991;
+pub(crate) const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// Serializes `duration` as a possibly fractional number of units, each `nanos_per_unit` long.
+pub(crate) fn serialize_fractional<S: ser::Serializer>(
+    duration: &Duration,
+    serializer: S,
+    nanos_per_unit: u32,
+    unit: &str,
+) -> Result<S::Ok, S::Error> {
+    let whole = duration.as_nanos() / u128::from(nanos_per_unit);
+    if whole > u128::from(MAX_SAFE_INTEGER) {
+        return Err(ser::Error::custom(format_args!(
+            "{:?} can't be represented as a JavaScript number of {}",
+            duration, unit
+        )));
+    }
+    let units_per_sec = f64::from(NANOS_PER_SEC / nanos_per_unit);
+    serializer.serialize_f64(
+        duration.as_secs() as f64 * units_per_sec
+            + f64::from(duration.subsec_nanos()) / f64::from(nanos_per_unit),
+    )
+}
+
+/// Deserializes a [`Duration`] from a non-negative, possibly fractional number of units,
+/// each `nanos_per_unit` long, keeping the fractional part with nanosecond precision.
+pub(crate) fn deserialize_fractional<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+    nanos_per_unit: u32,
+    expected: &'static str,
+) -> Result<Duration, D::Error> {
+    let value = <f64 as serde::Deserialize>::deserialize(deserializer)?;
+    if !(0.0..=MAX_SAFE_INTEGER as f64).contains(&value) {
+        return Err(serde::de::Error::invalid_value(
+            serde::de::Unexpected::Float(value),
+            &expected,
+        ));
+    }
+    let whole = value.trunc();
+    // Rounding up to a whole unit is carried over by `Duration::new`.
+    let fraction = ((value - whole) * f64::from(nanos_per_unit)).round() as u32;
+    let units_per_sec = u64::from(NANOS_PER_SEC / nanos_per_unit);
+    let whole = whole as u64;
+    Ok(Duration::new(
+        whole / units_per_sec,
+        (whole % units_per_sec) as u32 * nanos_per_unit + fraction,
+    ))
+}
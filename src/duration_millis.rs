@@ -0,0 +1,30 @@
+//! Serializes [`Duration`] as a single JavaScript number of milliseconds.
+//!
+//! Use with `#[serde(with = "serde_wasm_bindgen::duration_millis")]` on a [`Duration`] field to
+//! pass it straight to `setTimeout`-style JavaScript APIs. Fractional milliseconds, e.g. `1.5` for
+//! 1500 microseconds, are kept in both directions.
+
+use crate::duration::{deserialize_fractional, serialize_fractional};
+use serde::{de, ser};
+use std::time::Duration;
+
+/// Serializes a [`Duration`] as a possibly fractional number of milliseconds.
+///
+/// Fails if the number of whole milliseconds exceeds `Number.MAX_SAFE_INTEGER`.
+pub fn serialize<S: ser::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serialize_fractional(duration, serializer, 1_000_000, "milliseconds")
+}
+
+/// Deserializes a [`Duration`] from a non-negative number of milliseconds.
+///
+/// Fractional milliseconds are kept with nanosecond precision.
+pub fn deserialize<'de, D: de::Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    deserialize_fractional(
+        deserializer,
+        1_000_000,
+        "a non-negative safe number of milliseconds",
+    )
+}
@@ -0,0 +1,46 @@
+//! Serializes [`Duration`] as a single JavaScript number of nanoseconds.
+//!
+//! Use with `#[serde(with = "serde_wasm_bindgen::duration_nanos")]` on a [`Duration`] field.
+//! Durations up to `Number.MAX_SAFE_INTEGER` nanoseconds (about 104 days) become numbers, and
+//! longer ones become `BigInt`s to keep nanosecond precision. With
+//! `serialize_large_number_types_as_bigints(true)`, all of them become `BigInt`s, like any other
+//! `u64`.
+
+use crate::duration::MAX_SAFE_INTEGER;
+use crate::preserve::{with_handover, PRESERVED_VALUE_MAGIC};
+use serde::de::{self, Deserialize};
+use serde::ser;
+use std::time::Duration;
+use wasm_bindgen::JsValue;
+
+/// Serializes a [`Duration`] as a whole number of nanoseconds, or a `BigInt` of them beyond
+/// `Number.MAX_SAFE_INTEGER`.
+///
+/// Serializers other than this crate's one get a plain `u64` instead of a `BigInt`. Fails if
+/// the number of nanoseconds doesn't fit into a `u64`.
+pub fn serialize<S: ser::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let nanos = duration.as_nanos();
+    if nanos > u128::from(u64::MAX) {
+        return Err(ser::Error::custom(format_args!(
+            "{:?} has too many nanoseconds to fit into a u64",
+            duration
+        )));
+    }
+    let nanos = nanos as u64;
+    if nanos <= MAX_SAFE_INTEGER {
+        return serializer.serialize_u64(nanos);
+    }
+    // Hands the `BigInt` over like a `PreservedValue`, while other serializers see a newtype
+    // struct around the `u64`.
+    with_handover(JsValue::from(nanos), || {
+        serializer.serialize_newtype_struct(PRESERVED_VALUE_MAGIC, &nanos)
+    })
+}
+
+/// Deserializes a [`Duration`] from a safe integer number or a `BigInt` of nanoseconds.
+pub fn deserialize<'de, D: de::Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_nanos)
+}
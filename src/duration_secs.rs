@@ -0,0 +1,29 @@
+//! Serializes [`Duration`] as a single JavaScript number of seconds.
+//!
+//! Use with `#[serde(with = "serde_wasm_bindgen::duration_secs")]` on a [`Duration`] field.
+//! Fractional seconds, e.g. `1.5` for one and a half seconds, are kept in both directions.
+
+use crate::duration::{deserialize_fractional, serialize_fractional, NANOS_PER_SEC};
+use serde::{de, ser};
+use std::time::Duration;
+
+/// Serializes a [`Duration`] as a possibly fractional number of seconds.
+///
+/// Fails if the number of whole seconds exceeds `Number.MAX_SAFE_INTEGER`.
+pub fn serialize<S: ser::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serialize_fractional(duration, serializer, NANOS_PER_SEC, "seconds")
+}
+
+/// Deserializes a [`Duration`] from a non-negative number of seconds.
+///
+/// Fractional seconds are kept with nanosecond precision.
+pub fn deserialize<'de, D: de::Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    deserialize_fractional(
+        deserializer,
+        NANOS_PER_SEC,
+        "a non-negative safe number of seconds",
+    )
+}
@@ -1,17 +1,25 @@
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// The `name` of JavaScript errors produced by this crate, so that they can be told apart
+/// from other exceptions in `catch` blocks.
+const ERROR_NAME: &str = "SerdeWasmBindgenError";
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = String)]
+    fn to_string(value: &JsValue) -> String;
+}
 
 /// A newtype that represents Serde errors as JavaScript exceptions.
+///
+/// Converts into a JavaScript `Error` with `name` set to `"SerdeWasmBindgenError"`.
+/// Errors coming from JavaScript exceptions keep the original exception as their `cause`.
 #[derive(Debug)]
 pub struct Error(JsValue);
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        #[wasm_bindgen]
-        extern "C" {
-            #[wasm_bindgen(js_name = String)]
-            pub fn to_string(value: &JsValue) -> String;
-        }
-
         to_string(&self.0).fmt(f)
     }
 }
@@ -21,7 +29,16 @@ impl std::error::Error for Error {}
 impl Error {
     /// Creates a JavaScript `Error` with a given message.
     pub fn new<T: std::fmt::Display>(msg: T) -> Self {
-        Error(js_sys::Error::new(&msg.to_string()).into())
+        let error = js_sys::Error::new(&msg.to_string());
+        error.set_name(ERROR_NAME);
+        Error(error.into())
+    }
+
+    /// Creates a JavaScript `Error` with a given message, caused by a JavaScript exception.
+    pub(crate) fn with_cause<T: std::fmt::Display>(msg: T, cause: JsValue) -> Self {
+        let error = Self::new(msg);
+        error.0.unchecked_ref::<js_sys::Error>().set_cause(&cause);
+        error
     }
 }
 
@@ -39,9 +56,15 @@ impl serde::de::Error for Error {
 
 /// This conversion is needed for `?` to just work when using wasm-bindgen
 /// imports that return JavaScript exceptions as `Result<T, JsValue>`.
+///
+/// The exception becomes the `cause`, and its message is reused.
 impl From<JsValue> for Error {
     fn from(error: JsValue) -> Error {
-        Error(error)
+        let msg = match error.dyn_ref::<js_sys::Error>() {
+            Some(error) => String::from(error.message()),
+            None => to_string(&error),
+        };
+        Error::with_cause(msg, error)
     }
 }
 
@@ -2,28 +2,52 @@
 #![warn(missing_docs)]
 
 use js_sys::JsString;
+use std::collections::HashSet;
+use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 
+pub mod as_js_result;
+pub mod as_millis;
 mod bindings;
+mod bool_enum;
+mod case;
 mod de;
+mod duration;
+pub mod duration_millis;
+pub mod duration_nanos;
+pub mod duration_secs;
 mod error;
+mod preserve;
 mod ser;
+mod typed_arrays;
+mod zero_padded;
 
+pub use bool_enum::BoolEnum;
+pub use case::Case;
 pub use de::Deserializer;
 pub use error::Error;
-pub use ser::Serializer;
+pub use preserve::PreservedValue;
+pub use ser::{MissingMapValues, Serializer};
+pub use typed_arrays::Float32Slice;
+pub use zero_padded::ZeroPadded;
 
 type Result<T> = std::result::Result<T, Error>;
 
-fn static_str_to_js(s: &'static str) -> JsString {
+/// Returns a cached JavaScript copy of a static Rust string, such as a field or variant name.
+///
+/// The cache is keyed by the address and length of the string rather than its contents, which
+/// makes lookups cheap for the long field lists of nested structs. Values are reference-counted
+/// on the Rust side so that handing one out doesn't cost a round-trip to JavaScript to clone
+/// (and later drop) the underlying handle.
+fn static_str_to_js(s: &'static str) -> Rc<JsString> {
     thread_local! {
-        static CACHE: std::cell::RefCell<fnv::FnvHashMap<&'static str, JsString>> = Default::default();
+        static CACHE: std::cell::RefCell<fnv::FnvHashMap<(usize, usize), Rc<JsString>>> = Default::default();
     }
     CACHE.with(|cache| {
         cache
             .borrow_mut()
-            .entry(s)
-            .or_insert_with(|| s.into())
+            .entry((s.as_ptr() as usize, s.len()))
+            .or_insert_with(|| Rc::new(s.into()))
             .clone()
     })
 }
@@ -34,10 +58,10 @@ extern "C" {
     type ObjectExt;
 
     #[wasm_bindgen(method, indexing_getter)]
-    fn get(this: &ObjectExt, key: JsString) -> JsValue;
+    fn get(this: &ObjectExt, key: &JsString) -> JsValue;
 
     #[wasm_bindgen(method, indexing_setter)]
-    fn set(this: &ObjectExt, key: JsString, value: JsValue);
+    fn set(this: &ObjectExt, key: &JsString, value: JsValue);
 }
 
 /// Converts [`JsValue`] into a Rust type.
@@ -45,7 +69,106 @@ pub fn from_value<T: serde::de::DeserializeOwned>(value: JsValue) -> Result<T> {
     T::deserialize(Deserializer::from(value))
 }
 
+/// Converts [`JsValue`] into a Rust struct, collecting errors from all of its fields instead of
+/// stopping at the first one.
+///
+/// Each error is paired with the name of the top-level field it occurred in, or an empty string if
+/// it applies to the value as a whole (e.g. if it's not an object at all). Errors within nested
+/// values are reported for the top-level field containing them.
+///
+/// The value is deserialized once more for every invalid field, so this is best suited for
+/// validation-style flows where the value is expected to be mostly correct.
+pub fn try_from_value<T: serde::de::DeserializeOwned>(
+    value: JsValue,
+) -> std::result::Result<T, Vec<(String, Error)>> {
+    let field_errors = Rc::<de::FieldErrors>::default();
+    let mut errors = Vec::new();
+    loop {
+        let failed_count = field_errors.failed_count();
+        let error = match T::deserialize(Deserializer::with_field_errors(
+            value.clone(),
+            &field_errors,
+        )) {
+            Ok(value) if errors.is_empty() => return Ok(value),
+            Ok(_) => return Err(errors),
+            Err(error) => error,
+        };
+        match field_errors.failed_since(failed_count) {
+            // Try again without this field to find errors in the remaining ones.
+            Some(field) => errors.push((field.to_owned(), error)),
+            None => {
+                if !field_errors.is_missing_failed_field(&error) {
+                    errors.push((String::new(), error));
+                }
+                return Err(errors);
+            }
+        }
+    }
+}
+
+/// Converts [`JsValue`] into a Rust struct, along with the names of its fields that were present
+/// in the JavaScript object.
+///
+/// This allows telling apart a field that is absent from one explicitly set to `null`, e.g. for
+/// applying partial updates, as both become `None` for an `Option` field. Fields set to
+/// `undefined` are treated as absent. Only the fields of the top-level struct are tracked, by
+/// their names in the JavaScript object (after any `#[serde(rename)]`).
+pub fn from_value_tracking_presence<T: serde::de::DeserializeOwned>(
+    value: JsValue,
+) -> Result<(T, HashSet<String>)> {
+    let present_fields = Rc::<de::PresentFields>::default();
+    let value = T::deserialize(Deserializer::with_present_fields(value, &present_fields))?;
+    let present_fields = present_fields
+        .borrow()
+        .iter()
+        .map(|&field| field.to_owned())
+        .collect();
+    Ok((value, present_fields))
+}
+
+/// Creates a `Uint8Array` that is a view into the given bytes in the Wasm memory, without copying.
+///
+/// This is an escape hatch for handing large buffers over to JavaScript, e.g. to immediately
+/// copy them with `slice()` or write them to a stream. The regular [`to_value`] and
+/// `serde_bytes` path always produces a copy, and is what you want in most cases.
+///
+/// # Safety
+///
+/// The returned view is only valid while `bytes` is alive and isn't modified, and until the
+/// next allocation on the Rust side: when the Wasm memory grows, the view gets detached and
+/// appears empty to JavaScript. It must be consumed right away, before calling back into Rust.
+pub unsafe fn to_shared_bytes(bytes: &[u8]) -> js_sys::Uint8Array {
+    js_sys::Uint8Array::view(bytes)
+}
+
 /// Converts a Rust value into a [`JsValue`].
 pub fn to_value<T: serde::ser::Serialize + ?Sized>(value: &T) -> Result<JsValue> {
     value.serialize(&Serializer::new())
 }
+
+/// Converts a Rust sequence into [`JsValue`]s one element at a time, passing each of them to
+/// `callback` instead of collecting them into an `Array`.
+///
+/// See [`Serializer::serialize_seq_streaming`] for details.
+pub fn serialize_seq_streaming<T: serde::ser::Serialize + ?Sized>(
+    value: &T,
+    callback: &js_sys::Function,
+) -> Result<()> {
+    Serializer::new().serialize_seq_streaming(value, callback)
+}
+
+/// Converts a slice of Rust structs into a single JavaScript object with an `Array` of values
+/// per field, e.g. `{ id: [1, 2], name: ["a", "b"] }`.
+///
+/// See [`Serializer::serialize_columnar`] for details.
+pub fn to_columnar<T: serde::ser::Serialize>(rows: &[T]) -> Result<JsValue> {
+    Serializer::new().serialize_columnar(rows)
+}
+
+/// Converts a JavaScript object with an `Array` of values per field, as produced by
+/// [`to_columnar`], back into a `Vec` of Rust structs.
+///
+/// See [`Deserializer::deserialize_columnar`] for details.
+pub fn from_columnar<T: serde::de::DeserializeOwned>(value: JsValue) -> Result<Vec<T>> {
+    Deserializer::from(value).deserialize_columnar()
+}
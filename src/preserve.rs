@@ -0,0 +1,96 @@
+use serde::{de, ser};
+use std::cell::Cell;
+use std::fmt;
+use wasm_bindgen::JsValue;
+
+/// Name of the newtype struct the serializer and the deserializer in this crate recognise as a
+/// [`PreservedValue`] and pass through as-is instead of going through the Serde data model.
+pub(crate) const PRESERVED_VALUE_MAGIC: &str = "1fc430ca-5b7f-4295-92de-33cf2b145d38";
+
+thread_local! {
+    /// The value being handed over between a [`PreservedValue`] and our (de)serializer.
+    ///
+    /// Serde only passes Rust data between them, so the actual value goes around it, and the
+    /// newtype struct with [`PRESERVED_VALUE_MAGIC`] only signals that it's there.
+    static HANDOVER: Cell<Option<JsValue>> = const { Cell::new(None) };
+}
+
+/// Makes `value` available to [`take_handover`] while `f` runs.
+pub(crate) fn with_handover<R>(value: JsValue, f: impl FnOnce() -> R) -> R {
+    HANDOVER.with(|handover| handover.set(Some(value)));
+    let result = f();
+    // Don't leave the value behind if nobody took it, e.g. a wrong serializer.
+    HANDOVER.with(Cell::take);
+    result
+}
+
+/// Takes the value made available by [`with_handover`], if it's still there.
+pub(crate) fn take_handover() -> Option<JsValue> {
+    HANDOVER.with(Cell::take)
+}
+
+/// A [`JsValue`] that is embedded into the serialized output and captured during deserialization
+/// verbatim, without any conversion.
+///
+/// This is useful for passing through DOM nodes, functions and other JavaScript values that
+/// can't be represented in the Serde data model:
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use serde_wasm_bindgen::PreservedValue;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Widget {
+///     id: u32,
+///     element: PreservedValue,
+/// }
+/// ```
+///
+/// Note that this only works with [`Serializer`](crate::Serializer) and
+/// [`Deserializer`](crate::Deserializer); other serializers will see a unit value instead,
+/// while other deserializers will fail.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PreservedValue(pub JsValue);
+
+impl From<JsValue> for PreservedValue {
+    fn from(value: JsValue) -> Self {
+        Self(value)
+    }
+}
+
+impl From<PreservedValue> for JsValue {
+    fn from(value: PreservedValue) -> Self {
+        value.0
+    }
+}
+
+impl ser::Serialize for PreservedValue {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        with_handover(self.0.clone(), || {
+            serializer.serialize_newtype_struct(PRESERVED_VALUE_MAGIC, &())
+        })
+    }
+}
+
+impl<'de> de::Deserialize<'de> for PreservedValue {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = PreservedValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a JavaScript value passed through by serde-wasm-bindgen")
+            }
+
+            // Our `Deserializer` hands over the value right before calling this.
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                take_handover()
+                    .map(PreservedValue)
+                    .ok_or_else(|| de::Error::invalid_type(de::Unexpected::Unit, &self))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(PRESERVED_VALUE_MAGIC, Visitor)
+    }
+}
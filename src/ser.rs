@@ -1,30 +1,63 @@
 use crate::bindings;
-use js_sys::{Array, JsString, Map, Object, Uint8Array};
+use js_sys::{Array, DataView, JsString, Map, Number, Object, Uint8Array};
 use serde::ser::{self, Error as _, Serialize};
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::fmt;
+use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
-use super::{static_str_to_js, Error, ObjectExt};
+use super::preserve::{take_handover, PRESERVED_VALUE_MAGIC};
+use super::{static_str_to_js, Case, Error, ObjectExt};
 
 type Result<T = JsValue> = super::Result<T>;
 
 /// Wraps other serializers into an enum tagged variant form.
-/// Uses {"Variant": ...payload...} for compatibility with serde-json.
+/// Uses {"Variant": ...payload...} for compatibility with serde-json,
+/// or {0: ...payload...} with `variant_keys_as_index`.
+///
+/// Without a key, the payload is returned as-is, e.g. when the tag was already
+/// added to it by `struct_variant_tag`.
+///
+/// With `adjacent_variant_tag`, the key is the content key and the variant name goes
+/// into a separate tag field instead: {"__typename": "Variant", "data": ...payload...}.
+///
+/// With `variant_wrappers_as_maps`, the wrapper is a single-entry `Map` instead of an object.
 pub struct VariantSerializer<S> {
-    variant: &'static str,
+    key: Option<Rc<JsString>>,
+    tag: Option<(Rc<JsString>, Rc<JsString>)>,
+    as_map: bool,
+    null_prototype: bool,
     inner: S,
 }
 
 impl<S> VariantSerializer<S> {
-    pub fn new(variant: &'static str, inner: S) -> Self {
-        Self { variant, inner }
+    pub fn new(key: Option<Rc<JsString>>, inner: S) -> Self {
+        Self {
+            key,
+            tag: None,
+            as_map: false,
+            null_prototype: false,
+            inner,
+        }
     }
 
     fn end(self, inner: impl FnOnce(S) -> Result) -> Result {
         let value = inner(self.inner)?;
-        let obj = Object::new();
-        obj.unchecked_ref::<ObjectExt>()
-            .set(static_str_to_js(self.variant), value);
+        let key = match self.key {
+            Some(key) => key,
+            None => return Ok(value),
+        };
+        if self.as_map {
+            return Ok(Map::new().set(&key, &value).into());
+        }
+        let obj = new_object(self.null_prototype);
+        if let Some((tag, variant)) = self.tag {
+            obj.unchecked_ref::<ObjectExt>()
+                .set(&tag, JsString::clone(&variant).into());
+        }
+        obj.unchecked_ref::<ObjectExt>().set(&key, value);
         Ok(obj.into())
     }
 }
@@ -66,13 +99,23 @@ impl<S: ser::SerializeStruct<Ok = JsValue, Error = Error>> ser::SerializeStructV
 pub struct ArraySerializer<'s> {
     serializer: &'s Serializer,
     target: Array,
+    /// Number of elements so far when the array was preallocated from a length hint.
+    index: Option<u32>,
 }
 
 impl<'s> ArraySerializer<'s> {
-    pub fn new(serializer: &'s Serializer) -> Self {
-        Self {
-            serializer,
-            target: Array::new(),
+    pub fn new(serializer: &'s Serializer, len: Option<usize>) -> Self {
+        match len {
+            Some(len) => Self {
+                serializer,
+                target: Array::new_with_length(len as u32),
+                index: Some(0),
+            },
+            None => Self {
+                serializer,
+                target: Array::new(),
+                index: None,
+            },
         }
     }
 }
@@ -82,11 +125,26 @@ impl ser::SerializeSeq for ArraySerializer<'_> {
     type Error = Error;
 
     fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
-        self.target.push(&value.serialize(self.serializer)?);
+        let value = value.serialize(self.serializer)?;
+        match &mut self.index {
+            Some(index) => {
+                self.target.set(*index, value);
+                *index += 1;
+            }
+            None => {
+                self.target.push(&value);
+            }
+        }
         Ok(())
     }
 
     fn end(self) -> Result {
+        // Don't leave holes at the end if the hint was too optimistic.
+        if let Some(index) = self.index {
+            if index < self.target.length() {
+                self.target.set_length(index);
+            }
+        }
         Ok(self.target.into())
     }
 }
@@ -122,10 +180,104 @@ pub enum MapResult {
     Object(Object),
 }
 
+/// Order of serialized map keys when `sort_map_keys` is enabled.
+///
+/// Numbers and strings that JavaScript treats as array indices, i.e. canonical integers from
+/// `0` to `2^32 - 2` (`"0"`, `"10"`, but not `"010"` or `"4294967295"`), are compared numerically
+/// and go first, followed by the rest of strings compared by UTF-16 code units, like in
+/// `Array.prototype.sort`. This matches how JavaScript itself orders the index keys of plain
+/// objects ahead of the others. `NaN` goes after all other numbers, so that any keys can be sorted.
+enum MapKeyOrder {
+    Number(f64),
+    String(String),
+}
+
+/// Implements a total order for an enum of numbers and strings, with numbers going first.
+macro_rules! impl_number_or_string_ord {
+    ($($ty:ident),*) => {$(
+        impl Ord for $ty {
+            fn cmp(&self, other: &Self) -> Ordering {
+                match (self, other) {
+                    ($ty::Number(a), $ty::Number(b)) => a.total_cmp(b),
+                    ($ty::String(a), $ty::String(b)) => a.encode_utf16().cmp(b.encode_utf16()),
+                    ($ty::Number(_), $ty::String(_)) => Ordering::Less,
+                    ($ty::String(_), $ty::Number(_)) => Ordering::Greater,
+                }
+            }
+        }
+
+        impl PartialOrd for $ty {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl PartialEq for $ty {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+
+        impl Eq for $ty {}
+    )*};
+}
+
+impl_number_or_string_ord!(MapKeyOrder);
+
+impl MapKeyOrder {
+    fn new(key: &JsValue) -> Result<Self> {
+        if let Some(v) = key.as_f64() {
+            Ok(MapKeyOrder::Number(v))
+        } else if let Some(v) = key.as_string() {
+            // Same as the array index check of JavaScript: `ToString(ToUint32(key)) === key`,
+            // excluding `2^32 - 1`, which is not a valid index.
+            Ok(match v.parse::<u32>() {
+                Ok(index) if index != u32::MAX && index.to_string() == v => {
+                    MapKeyOrder::Number(index.into())
+                }
+                _ => MapKeyOrder::String(v),
+            })
+        } else {
+            Err(Error::custom(
+                "Map key is not a number or a string and cannot be sorted",
+            ))
+        }
+    }
+}
+
+/// Order of serialized map values when `sort_map_values` is enabled.
+///
+/// Numbers are compared numerically and go first, followed by strings compared by UTF-16 code
+/// units, like in `Array.prototype.sort`. `NaN` goes after all other numbers, like for keys.
+enum MapValueOrder {
+    Number(f64),
+    String(String),
+}
+
+impl_number_or_string_ord!(MapValueOrder);
+
+impl MapValueOrder {
+    fn new(value: &JsValue) -> Result<Self> {
+        if let Some(v) = value.as_f64() {
+            Ok(MapValueOrder::Number(v))
+        } else if let Some(v) = value.as_string() {
+            Ok(MapValueOrder::String(v))
+        } else {
+            Err(Error::custom(
+                "Map value is not a number or a string and cannot be sorted",
+            ))
+        }
+    }
+}
+
+/// Entries are sorted by value first, if enabled, and then by key.
+type MapEntryOrder = (Option<MapValueOrder>, Option<MapKeyOrder>);
+
 pub struct MapSerializer<'s> {
     serializer: &'s Serializer,
     target: MapResult,
     next_key: Option<JsValue>,
+    sorted_entries: Option<Vec<(MapEntryOrder, JsValue, JsValue)>>,
 }
 
 impl<'s> MapSerializer<'s> {
@@ -133,12 +285,36 @@ impl<'s> MapSerializer<'s> {
         Self {
             serializer,
             target: if as_object {
-                MapResult::Object(Object::new())
+                MapResult::Object(serializer.new_object())
             } else {
                 MapResult::Map(Map::new())
             },
             next_key: None,
+            sorted_entries: if serializer.sort_map_keys || serializer.sort_map_values {
+                Some(Vec::new())
+            } else {
+                None
+            },
+        }
+    }
+
+    fn insert(&self, key: JsValue, value: JsValue) -> Result<()> {
+        match &self.target {
+            MapResult::Map(map) => {
+                map.set(&key, &value);
+            }
+            MapResult::Object(object) => {
+                let key = if let Some(number) = key.dyn_ref::<Number>() {
+                    bindings::number_to_string(number)
+                } else {
+                    key.dyn_into::<JsString>().map_err(|_| {
+                        Error::custom("Map key is not a string and cannot be an object key")
+                    })?
+                };
+                object.unchecked_ref::<ObjectExt>().set(&key, value);
+            }
         }
+        Ok(())
     }
 }
 
@@ -148,29 +324,71 @@ impl ser::SerializeMap for MapSerializer<'_> {
 
     fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
         debug_assert!(self.next_key.is_none());
-        self.next_key = Some(key.serialize(self.serializer)?);
+        let mut key = match &self.serializer.map_key_cache {
+            Some(cache) => key.serialize(MapKeySerializer {
+                serializer: self.serializer,
+                cache,
+            })?,
+            None => key.serialize(self.serializer)?,
+        };
+        if self.serializer.stringify_map_keys && key.is_object() {
+            key = match js_sys::JSON::stringify(&key) {
+                Ok(json) if json.is_string() => json.into(),
+                Ok(_) => return Err(Error::custom("Map key cannot be converted to JSON")),
+                Err(err) => {
+                    return Err(Error::with_cause(
+                        "Map key cannot be converted to JSON",
+                        err,
+                    ))
+                }
+            };
+        }
+        self.next_key = Some(key);
         Ok(())
     }
 
     fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
         let key = self.next_key.take().unwrap();
-        let value_ser = value.serialize(self.serializer)?;
-        match &self.target {
-            MapResult::Map(map) => {
-                map.set(&key, &value_ser);
+        self.serializer.serialized_none.set(false);
+        let mut value_ser = value.serialize(self.serializer)?;
+        // Containers of `None`s can set the flag too, but aren't serialized to `undefined`.
+        if self.serializer.serialized_none.get()
+            && (value_ser.is_null() || value_ser.is_undefined())
+        {
+            match self.serializer.missing_map_values {
+                MissingMapValues::Inherit => {}
+                MissingMapValues::Undefined => value_ser = JsValue::UNDEFINED,
+                MissingMapValues::Null => value_ser = JsValue::NULL,
+                MissingMapValues::Omit => return Ok(()),
             }
-            MapResult::Object(object) => {
-                let key = key.dyn_into::<JsString>().map_err(|_| {
-                    Error::custom("Map key is not a string and cannot be an object key")
-                })?;
-                object.unchecked_ref::<ObjectExt>().set(key, value_ser);
+        }
+        match &mut self.sorted_entries {
+            Some(entries) => {
+                let value_order = if self.serializer.sort_map_values {
+                    Some(MapValueOrder::new(&value_ser)?)
+                } else {
+                    None
+                };
+                let key_order = if self.serializer.sort_map_keys {
+                    Some(MapKeyOrder::new(&key)?)
+                } else {
+                    None
+                };
+                entries.push(((value_order, key_order), key, value_ser));
+                Ok(())
             }
+            None => self.insert(key, value_ser),
         }
-        Ok(())
     }
 
-    fn end(self) -> Result {
+    fn end(mut self) -> Result {
         debug_assert!(self.next_key.is_none());
+        if let Some(mut entries) = self.sorted_entries.take() {
+            entries.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+            for (_, key, value) in entries {
+                self.insert(key, value)?;
+            }
+        }
         match self.target {
             MapResult::Map(map) => Ok(map.into()),
             MapResult::Object(object) => Ok(object.into()),
@@ -187,7 +405,7 @@ impl<'s> ObjectSerializer<'s> {
     pub fn new(serializer: &'s Serializer) -> Self {
         Self {
             serializer,
-            target: Object::new().unchecked_into::<ObjectExt>(),
+            target: serializer.new_object().unchecked_into::<ObjectExt>(),
         }
     }
 }
@@ -196,13 +414,19 @@ impl ser::SerializeStruct for ObjectSerializer<'_> {
     type Ok = JsValue;
     type Error = Error;
 
+    /// Fields are set in the order serde passes them, which is their declaration order, and
+    /// JS objects keep string keys in insertion order, so `Object.keys` lists them the same way.
+    /// The only exception are integer-like keys (e.g. `#[serde(rename = "1")]`), which JS
+    /// always lists first in ascending order.
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
         key: &'static str,
         value: &T,
     ) -> Result<()> {
         let value = value.serialize(self.serializer)?;
-        self.target.set(static_str_to_js(key), value);
+        if !(self.serializer.skip_none_fields && value.is_undefined()) {
+            self.target.set(&static_str_to_js(key), value);
+        }
         Ok(())
     }
 
@@ -211,12 +435,118 @@ impl ser::SerializeStruct for ObjectSerializer<'_> {
     }
 }
 
+/// Representation of `None` values in serialized maps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingMapValues {
+    /// Same as anywhere else, as configured by `serialize_missing_as_null`.
+    #[default]
+    Inherit,
+    /// Store `undefined`. Note that `JSON.stringify` drops object properties with such values.
+    Undefined,
+    /// Store `null`.
+    Null,
+    /// Don't store the entry at all.
+    Omit,
+}
+
 /// A [`serde::Serializer`] that converts supported Rust values into a [`JsValue`].
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Serializer {
     serialize_missing_as_null: bool,
+    serialize_some_none_as_null: bool,
     serialize_maps_as_objects: bool,
     serialize_large_number_types_as_bigints: bool,
+    seal_byte_arrays: bool,
+    bytes_as_dataview: bool,
+    serialize_f32_as_shortest_decimal: bool,
+    bools_as_numbers: bool,
+    serialize_char_as_number: bool,
+    sort_map_keys: bool,
+    sort_map_values: bool,
+    stringify_map_keys: bool,
+    annotate_types: bool,
+    unit_variants_as_index: bool,
+    variant_keys_as_index: bool,
+    struct_variant_tag: Option<&'static str>,
+    adjacent_variant_tag: Option<(&'static str, &'static str)>,
+    indexed_variant_tag: Option<(&'static str, &'static str)>,
+    variant_wrappers_as_maps: bool,
+    variant_case: Case,
+    raw_variant: Option<&'static str>,
+    null_prototype_objects: bool,
+    missing_map_values: MissingMapValues,
+    skip_none_fields: bool,
+    /// Shared with clones of the serializer.
+    map_key_cache: Option<Rc<RefCell<fnv::FnvHashMap<String, JsValue>>>>,
+    /// Set by `serialize_none` so that map values can tell `None` apart from other values
+    /// serialized to `null` or `undefined`.
+    serialized_none: Cell<bool>,
+}
+impl fmt::Debug for Serializer {
+    /// Lists the options, but not the contents of the `cache_map_keys` cache or internal state.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Serializer {
+            serialize_missing_as_null,
+            serialize_some_none_as_null,
+            serialize_maps_as_objects,
+            serialize_large_number_types_as_bigints,
+            seal_byte_arrays,
+            bytes_as_dataview,
+            serialize_f32_as_shortest_decimal,
+            bools_as_numbers,
+            serialize_char_as_number,
+            sort_map_keys,
+            sort_map_values,
+            stringify_map_keys,
+            annotate_types,
+            unit_variants_as_index,
+            variant_keys_as_index,
+            struct_variant_tag,
+            adjacent_variant_tag,
+            indexed_variant_tag,
+            variant_wrappers_as_maps,
+            variant_case,
+            raw_variant,
+            null_prototype_objects,
+            missing_map_values,
+            skip_none_fields,
+            map_key_cache,
+            serialized_none: _,
+        } = self;
+        f.debug_struct("Serializer")
+            .field("serialize_missing_as_null", serialize_missing_as_null)
+            .field("serialize_some_none_as_null", serialize_some_none_as_null)
+            .field("serialize_maps_as_objects", serialize_maps_as_objects)
+            .field(
+                "serialize_large_number_types_as_bigints",
+                serialize_large_number_types_as_bigints,
+            )
+            .field("seal_byte_arrays", seal_byte_arrays)
+            .field("bytes_as_dataview", bytes_as_dataview)
+            .field(
+                "serialize_f32_as_shortest_decimal",
+                serialize_f32_as_shortest_decimal,
+            )
+            .field("bools_as_numbers", bools_as_numbers)
+            .field("serialize_char_as_number", serialize_char_as_number)
+            .field("sort_map_keys", sort_map_keys)
+            .field("sort_map_values", sort_map_values)
+            .field("stringify_map_keys", stringify_map_keys)
+            .field("annotate_types", annotate_types)
+            .field("unit_variants_as_index", unit_variants_as_index)
+            .field("variant_keys_as_index", variant_keys_as_index)
+            .field("struct_variant_tag", struct_variant_tag)
+            .field("adjacent_variant_tag", adjacent_variant_tag)
+            .field("indexed_variant_tag", indexed_variant_tag)
+            .field("variant_wrappers_as_maps", variant_wrappers_as_maps)
+            .field("variant_case", variant_case)
+            .field("raw_variant", raw_variant)
+            .field("null_prototype_objects", null_prototype_objects)
+            .field("missing_map_values", missing_map_values)
+            .field("skip_none_fields", skip_none_fields)
+            .field("cache_map_keys", &map_key_cache.is_some())
+            .finish()
+    }
 }
 
 impl Serializer {
@@ -233,19 +563,37 @@ impl Serializer {
         Self {
             serialize_missing_as_null: true,
             serialize_maps_as_objects: true,
-            serialize_large_number_types_as_bigints: false,
+            ..Self::new()
         }
     }
 
     /// Set to `true` to serialize `()`, unit structs and `Option::None` to `null`
     /// instead of `undefined` in JS. `false` by default.
+    #[must_use]
     pub fn serialize_missing_as_null(mut self, value: bool) -> Self {
         self.serialize_missing_as_null = value;
         self
     }
 
+    /// Set to `true` to serialize `Some(None)` (as well as `Some(())` and other missing values
+    /// wrapped in `Some`) to `null`, while a plain `None` stays `undefined`. `false` by default.
+    ///
+    /// Together with `#[serde(skip_serializing_if = "Option::is_none")]` on
+    /// an `Option<Option<T>>` field, this allows telling "absent" and "explicitly `null`" apart,
+    /// as in JSON merge patches. This has no effect with `serialize_missing_as_null`, which
+    /// already maps both to `null`.
+    ///
+    /// Note that Serde doesn't tell deserializers how many options are nested, so on the way
+    /// back both `null` and `undefined` become an outer `None`.
+    #[must_use]
+    pub fn serialize_some_none_as_null(mut self, value: bool) -> Self {
+        self.serialize_some_none_as_null = value;
+        self
+    }
+
     /// Set to `true` to serialize maps into plain JavaScript objects instead of
     /// ES2015 `Map`s. `false` by default.
+    #[must_use]
     pub fn serialize_maps_as_objects(mut self, value: bool) -> Self {
         self.serialize_maps_as_objects = value;
         self
@@ -253,10 +601,341 @@ impl Serializer {
 
     /// Set to `true` to serialize 64-bit numbers to JavaScript `BigInt` instead of
     /// plain numbers. `false` by default.
+    #[must_use]
     pub fn serialize_large_number_types_as_bigints(mut self, value: bool) -> Self {
         self.serialize_large_number_types_as_bigints = value;
         self
     }
+
+    /// Set to `true` to seal `Uint8Array`s produced for byte buffers via `Object.seal`.
+    /// `false` by default.
+    ///
+    /// JavaScript doesn't allow freezing typed arrays that have elements, nor making their
+    /// backing `ArrayBuffer` read-only, so this is the strongest guarantee available: no
+    /// properties can be added or removed, but elements themselves remain writable.
+    /// Empty arrays end up fully frozen.
+    ///
+    /// Engines following ES2021 and later throw when sealing a non-empty typed array, since
+    /// its elements can't be made non-configurable. Such arrays are made non-extensible via
+    /// `Object.preventExtensions` instead, which has the same effect because typed array
+    /// elements can't be deleted anyway, but `Object.isSealed` reports `false` for them.
+    #[must_use]
+    pub fn seal_byte_arrays(mut self, value: bool) -> Self {
+        self.seal_byte_arrays = value;
+        self
+    }
+
+    /// Set to `true` to serialize byte buffers as a `DataView` over a new `ArrayBuffer`
+    /// instead of a `Uint8Array`. `false` by default.
+    ///
+    /// This only changes the JavaScript wrapper type, e.g. for binary parsers consuming
+    /// `DataView`s: the bytes are copied out of the Wasm memory the same way in both cases.
+    /// `seal_byte_arrays` applies to the `DataView` instead.
+    #[must_use]
+    pub fn bytes_as_dataview(mut self, value: bool) -> Self {
+        self.bytes_as_dataview = value;
+        self
+    }
+
+    /// Set to `true` to serialize `f32` as the JavaScript number closest to its shortest
+    /// round-trippable decimal representation (e.g. `0.1f32` becomes `0.1`) instead of
+    /// widening it to `f64` (`0.10000000149011612`). `false` by default.
+    ///
+    /// JavaScript only has `f64` numbers, so this is purely about which `f64` gets picked;
+    /// either way the value deserializes back into the same `f32`.
+    #[must_use]
+    pub fn serialize_f32_as_shortest_decimal(mut self, value: bool) -> Self {
+        self.serialize_f32_as_shortest_decimal = value;
+        self
+    }
+
+    /// Set to `true` to serialize `bool` as `1` or `0` numbers instead of JavaScript booleans,
+    /// e.g. for protocols encoding flags as numbers. `false` by default.
+    #[must_use]
+    pub fn bools_as_numbers(mut self, value: bool) -> Self {
+        self.bools_as_numbers = value;
+        self
+    }
+
+    /// Set to `true` to serialize `char` as a number holding its Unicode code point
+    /// instead of a single-character string. `false` by default.
+    #[must_use]
+    pub fn serialize_char_as_number(mut self, value: bool) -> Self {
+        self.serialize_char_as_number = value;
+        self
+    }
+
+    /// Set to `true` to sort map entries by key before building the resulting `Map` or object.
+    /// `false` by default.
+    ///
+    /// Sorting is numeric-aware: numbers and strings that are valid array indices in JavaScript
+    /// (canonical integers below `2^32 - 1`) are ordered by their value (`1, 2, 10` rather than
+    /// `1, 10, 2`) and go before other strings, which are compared by UTF-16 code units. Keys of
+    /// any other type produce an error.
+    #[must_use]
+    pub fn sort_map_keys(mut self, value: bool) -> Self {
+        self.sort_map_keys = value;
+        self
+    }
+
+    /// Set to `true` to sort map entries by value before building the resulting `Map` or object.
+    /// `false` by default.
+    ///
+    /// Numbers go first in ascending order, followed by strings compared by UTF-16 code units.
+    /// Values of any other type produce an error. Entries with equal values keep their original order, unless `sort_map_keys` is
+    /// enabled as well, in which case they are sorted by key.
+    #[must_use]
+    pub fn sort_map_values(mut self, value: bool) -> Self {
+        self.sort_map_values = value;
+        self
+    }
+
+    /// Set to `true` to replace map keys that serialize to objects or arrays (e.g. structs) with
+    /// their `JSON.stringify` form. `false` by default.
+    ///
+    /// Such keys would otherwise become a distinct object in every `Map`, so they can't be looked
+    /// up on the JavaScript side, and can't be used in plain objects at all. Keys that can't be
+    /// stringified (e.g. containing `BigInt`s) produce an error. Nested `Map`s are stringified
+    /// as `{}`, so this is best combined with `serialize_maps_as_objects`.
+    #[must_use]
+    pub fn stringify_map_keys(mut self, value: bool) -> Self {
+        self.stringify_map_keys = value;
+        self
+    }
+
+    /// Set to `true` to add a non-enumerable `$rustType` property holding the Rust type name
+    /// to every object serialized from a struct or a struct variant. `false` by default.
+    ///
+    /// This is meant for inspecting payloads in browser devtools; being non-enumerable,
+    /// the property doesn't show up in `Object.keys`, `for...in` or `JSON.stringify`.
+    #[must_use]
+    pub fn annotate_types(mut self, value: bool) -> Self {
+        self.annotate_types = value;
+        self
+    }
+
+    /// Set to `true` to serialize unit variants of enums as their numeric index
+    /// instead of the `"Variant"` string. `false` by default.
+    ///
+    /// This produces more compact output, but is not compatible with serde-json.
+    #[must_use]
+    pub fn unit_variants_as_index(mut self, value: bool) -> Self {
+        self.unit_variants_as_index = value;
+        self
+    }
+
+    /// Set to `true` to serialize data-carrying variants of enums as objects keyed by
+    /// their numeric index (`{ 2: payload }`) instead of the `"Variant"` name. `false` by default.
+    ///
+    /// Like `unit_variants_as_index`, this is more compact, but not compatible with serde-json.
+    #[must_use]
+    pub fn variant_keys_as_index(mut self, value: bool) -> Self {
+        self.variant_keys_as_index = value;
+        self
+    }
+
+    /// Set to `true` to wrap data-carrying variants of enums into a single-entry ES2015 `Map`
+    /// (`new Map([["Variant", payload]])`) instead of a plain object. `false` by default.
+    ///
+    /// This keeps variant names that aren't valid JavaScript identifiers after
+    /// `#[serde(rename = "...")]`, like `"has space"`, unambiguous as `Map` keys. Other
+    /// representations, like `adjacent_variant_tag`, aren't affected.
+    #[must_use]
+    pub fn variant_wrappers_as_maps(mut self, value: bool) -> Self {
+        self.variant_wrappers_as_maps = value;
+        self
+    }
+
+    /// Sets the case convention to convert enum variant names to, e.g. `InProgress` to
+    /// `IN_PROGRESS` with [`Case::ScreamingSnake`]. [`Case::Unchanged`] by default.
+    ///
+    /// This works like `#[serde(rename_all = "...")]`, but applies to all enums at runtime.
+    /// Variant names are converted wherever they are used, including object keys and tags.
+    #[must_use]
+    pub fn variant_case(mut self, case: Case) -> Self {
+        self.variant_case = case;
+        self
+    }
+
+    /// Set to a variant name like `Some("Raw")` to serialize newtype variants with this name
+    /// as their payload alone, without any wrapper or tag. `None` by default.
+    ///
+    /// Together with the same option on the [`Deserializer`](crate::Deserializer) and
+    /// a `Raw(PreservedValue)` variant, this allows round-tripping variants unknown to
+    /// the Rust side verbatim.
+    #[must_use]
+    pub fn raw_variant(mut self, variant: Option<&'static str>) -> Self {
+        self.raw_variant = variant;
+        self
+    }
+
+    /// Set to a key name like `Some("type")` to serialize struct variants of enums as
+    /// their fields with an extra tag field holding the variant name (`{ type: "Point", x, y }`)
+    /// instead of `{ Point: { x, y } }`. `None` by default.
+    ///
+    /// This is the same shape as `#[serde(tag = "type")]` produces, and is common for
+    /// TypeScript discriminated unions, but doesn't require changing the Rust types.
+    /// Other variants aren't affected.
+    #[must_use]
+    pub fn struct_variant_tag(mut self, tag: Option<&'static str>) -> Self {
+        self.struct_variant_tag = tag;
+        self
+    }
+
+    /// Set to tag and content key names like `Some(("__typename", "data"))` to serialize
+    /// all variants of enums as an object with the variant name under the tag key and the
+    /// payload under the content key (`{ __typename: "Point", data: { x, y } }`) instead of
+    /// `{ Point: { x, y } }`. Unit variants only get the tag key (`{ __typename: "Unit" }`).
+    /// `None` by default.
+    ///
+    /// This doesn't require changing the Rust types, and takes precedence over
+    /// `unit_variants_as_index` and `struct_variant_tag`.
+    #[must_use]
+    pub fn adjacent_variant_tag(mut self, keys: Option<(&'static str, &'static str)>) -> Self {
+        self.adjacent_variant_tag = keys;
+        self
+    }
+
+    /// Set to tag and index key names like `Some(("kind", "index"))` to serialize struct variants
+    /// of enums as their fields with two extra fields holding the variant name and its numeric
+    /// index (`{ kind: "Point", index: 2, x, y }`), and unit variants as objects with just these
+    /// two fields (`{ kind: "Unit", index: 0 }`). `None` by default.
+    ///
+    /// This suits code generators that want both a readable discriminant and a stable number to
+    /// switch on. Newtype and tuple variants aren't affected. Takes precedence over
+    /// `unit_variants_as_index` and `struct_variant_tag`, but not `adjacent_variant_tag`.
+    #[must_use]
+    pub fn indexed_variant_tag(mut self, keys: Option<(&'static str, &'static str)>) -> Self {
+        self.indexed_variant_tag = keys;
+        self
+    }
+
+    /// Set to `true` to create objects for structs, maps (when serialized as objects) and
+    /// enum variants via `Object.create(null)`. `false` by default.
+    ///
+    /// Such objects have no prototype, so keys like `"__proto__"` or `"constructor"` coming
+    /// from untrusted data become regular own properties instead of affecting the prototype chain.
+    #[must_use]
+    pub fn null_prototype_objects(mut self, value: bool) -> Self {
+        self.null_prototype_objects = value;
+        self
+    }
+
+    /// Sets how `None` values are stored in both `Map`s and objects produced for Rust maps.
+    /// [`MissingMapValues::Inherit`] by default.
+    ///
+    /// Only values that are `None` on the Rust side count as missing. Other values serialized
+    /// to `null` or `undefined`, like `()`, a `PreservedValue` of `null`, or `Some(None)` with
+    /// `serialize_some_none_as_null`, are always stored as they are.
+    #[must_use]
+    pub fn missing_map_values(mut self, value: MissingMapValues) -> Self {
+        self.missing_map_values = value;
+        self
+    }
+
+    /// Set to `true` to omit struct fields that serialize to `undefined` (`None`, `()` and
+    /// unit structs), which keeps objects for sparse structs small without annotating every
+    /// field with `#[serde(skip_serializing_if = "...")]`. `false` by default.
+    ///
+    /// This is only about `undefined`: fields holding default values like `0` or `""` are kept,
+    /// and so are missing values serialized as `null` via `serialize_missing_as_null`.
+    #[must_use]
+    pub fn skip_none_fields(mut self, value: bool) -> Self {
+        self.skip_none_fields = value;
+        self
+    }
+
+    /// Set to `true` to cache JavaScript strings created for string keys of Rust maps, so that
+    /// keys repeated across many maps, e.g. in rows of a `Vec<HashMap<String, T>>`, are copied
+    /// out of the Wasm memory only once. `false` by default.
+    ///
+    /// The cache lives as long as the serializer and keeps every distinct key, so it's meant for
+    /// bulk serialization of maps sharing a limited set of keys. Clones of the serializer share
+    /// the cache with it, while calling this method again starts a new one.
+    #[must_use]
+    pub fn cache_map_keys(mut self, value: bool) -> Self {
+        self.map_key_cache = if value {
+            Some(Default::default())
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Serializes a sequence, a tuple (including fixed-size arrays and tuple structs) or a newtype
+    /// wrapper around one element by element, passing each element to `callback` right away
+    /// instead of collecting them into an `Array`.
+    ///
+    /// This keeps memory usage low when exporting large datasets, e.g. to a `ReadableStream`.
+    /// Errors thrown by `callback` stop the serialization and are returned as the `cause` of
+    /// the resulting error. Values other than sequences are rejected.
+    pub fn serialize_seq_streaming<T: ?Sized + Serialize>(
+        &self,
+        value: &T,
+        callback: &js_sys::Function,
+    ) -> Result<()> {
+        value.serialize(StreamingSerializer::new(self, callback))
+    }
+
+    /// Serializes structs as a single object with an `Array` of values per field, e.g.
+    /// `{ id: [1, 2], name: ["a", "b"] }` for two rows, instead of an `Array` of objects.
+    ///
+    /// Such struct-of-arrays layout is more compact and usually preferred by analytics and
+    /// charting libraries. Fields are serialized with the same options as anywhere else, and
+    /// columns follow the order in which fields are first seen. Fields missing from some of
+    /// the rows due to `#[serde(skip_serializing_if = "...")]` get `undefined` in those rows.
+    /// Rows that aren't structs are rejected. No rows produce an empty object.
+    pub fn serialize_columnar<T: Serialize>(&self, rows: &[T]) -> Result {
+        let mut columns = ColumnarSerializer::new(self);
+        for row in rows {
+            columns.serialize_row(row)?;
+        }
+        columns.end()
+    }
+
+    fn variant_key(&self, variant_index: u32, variant: &'static str) -> Rc<JsString> {
+        if self.variant_keys_as_index {
+            Rc::new(variant_index.to_string().into())
+        } else {
+            static_str_to_js(self.variant_case.apply(variant))
+        }
+    }
+
+    fn variant_serializer<S>(
+        &self,
+        variant_index: u32,
+        variant: &'static str,
+        inner: S,
+    ) -> VariantSerializer<S> {
+        let key = self.variant_key(variant_index, variant);
+        match self.adjacent_variant_tag {
+            Some((tag, content)) => VariantSerializer {
+                key: Some(static_str_to_js(content)),
+                tag: Some((static_str_to_js(tag), key)),
+                as_map: false,
+                null_prototype: self.null_prototype_objects,
+                inner,
+            },
+            None => VariantSerializer {
+                as_map: self.variant_wrappers_as_maps,
+                null_prototype: self.null_prototype_objects,
+                ..VariantSerializer::new(Some(key), inner)
+            },
+        }
+    }
+
+    fn new_object(&self) -> Object {
+        new_object(self.null_prototype_objects)
+    }
+}
+
+/// Creates an empty object, without a prototype if `null_prototype` is set.
+fn new_object(null_prototype: bool) -> Object {
+    if null_prototype {
+        Object::create(JsValue::NULL.unchecked_ref())
+    } else {
+        Object::new()
+    }
 }
 
 macro_rules! forward_to_into {
@@ -280,8 +959,6 @@ impl<'s> ser::Serializer for &'s Serializer {
     type SerializeStructVariant = VariantSerializer<ObjectSerializer<'s>>;
 
     forward_to_into! {
-        serialize_bool(bool);
-
         serialize_i8(i8);
         serialize_i16(i16);
         serialize_i32(i32);
@@ -290,12 +967,33 @@ impl<'s> ser::Serializer for &'s Serializer {
         serialize_u16(u16);
         serialize_u32(u32);
 
-        serialize_f32(f32);
         serialize_f64(f64);
 
         serialize_str(&str);
     }
 
+    fn serialize_bool(self, v: bool) -> Result {
+        if self.bools_as_numbers {
+            return self.serialize_u8(v.into());
+        }
+        Ok(v.into())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result {
+        if self.serialize_f32_as_shortest_decimal && v.is_finite() {
+            use std::io::Write;
+
+            // Exponential notation keeps the shortest representation well within the buffer.
+            let mut buf = [0; 32];
+            let mut cursor = &mut buf[..];
+            write!(cursor, "{:e}", v).unwrap();
+            let len = 32 - cursor.len();
+            let repr = std::str::from_utf8(&buf[..len]).unwrap();
+            return self.serialize_f64(repr.parse().unwrap());
+        }
+        Ok(v.into())
+    }
+
     fn serialize_i64(self, v: i64) -> Result {
         if self.serialize_large_number_types_as_bigints {
             return Ok(bindings::bigint_from_i64(v).into());
@@ -303,7 +1001,7 @@ impl<'s> ser::Serializer for &'s Serializer {
         const MAX_SAFE_INTEGER: i64 = 9_007_199_254_740_991;
         const MIN_SAFE_INTEGER: i64 = -MAX_SAFE_INTEGER;
 
-        if v >= MIN_SAFE_INTEGER && v <= MAX_SAFE_INTEGER {
+        if (MIN_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&v) {
             self.serialize_f64(v as _)
         } else {
             Err(Error::custom(format_args!(
@@ -347,6 +1045,9 @@ impl<'s> ser::Serializer for &'s Serializer {
     }
 
     fn serialize_char(self, v: char) -> Result {
+        if self.serialize_char_as_number {
+            return self.serialize_u32(v.into());
+        }
         Ok(JsString::from(v).into())
     }
 
@@ -355,17 +1056,37 @@ impl<'s> ser::Serializer for &'s Serializer {
         //
         // This is necessary because any allocation in WebAssembly can require reallocation of the
         // backing memory, which will invalidate existing views (including `Uint8Array`).
-        Ok(JsValue::from(Uint8Array::new(
-            unsafe { Uint8Array::view(v) }.as_ref(),
-        )))
+        let array = Uint8Array::new(unsafe { Uint8Array::view(v) }.as_ref());
+        let value: Object = if self.bytes_as_dataview {
+            DataView::new(&array.buffer(), 0, v.len()).into()
+        } else {
+            array.into()
+        };
+        if self.seal_byte_arrays && bindings::try_seal(&value).is_err() {
+            Object::prevent_extensions(&value);
+        }
+        Ok(value.into())
     }
 
+    /// Serialises `None` as `undefined`, or `null` with `serialize_missing_as_null`,
+    /// including at the top level.
     fn serialize_none(self) -> Result {
+        self.serialized_none.set(true);
         self.serialize_unit()
     }
 
+    /// Serialises `Some(value)` as the value itself, without any extra layer, so that nested
+    /// options like `Some(Some(value))` end up as the same JS value too.
+    ///
+    /// The only exception is an inner missing value with `serialize_some_none_as_null`, which
+    /// becomes `null` to tell `Some(None)` apart from `None`.
     fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result {
-        value.serialize(self)
+        let value = value.serialize(self)?;
+        self.serialized_none.set(false);
+        if self.serialize_some_none_as_null && value.is_undefined() {
+            return Ok(JsValue::NULL);
+        }
+        Ok(value)
     }
 
     fn serialize_unit(self) -> Result {
@@ -380,38 +1101,77 @@ impl<'s> ser::Serializer for &'s Serializer {
         self.serialize_unit()
     }
 
-    /// For compatibility with serde-json, serialises unit variants as "Variant" strings.
+    /// For compatibility with serde-json, serialises unit variants as "Variant" strings,
+    /// unless `unit_variants_as_index`, `adjacent_variant_tag` or `indexed_variant_tag` is enabled.
     fn serialize_unit_variant(
         self,
         _name: &'static str,
-        _variant_index: u32,
+        variant_index: u32,
         variant: &'static str,
     ) -> Result {
-        Ok(static_str_to_js(variant).into())
+        if let Some((tag, _)) = self.adjacent_variant_tag {
+            let obj = self.new_object();
+            obj.unchecked_ref::<ObjectExt>().set(
+                &static_str_to_js(tag),
+                JsString::clone(&self.variant_key(variant_index, variant)).into(),
+            );
+            return Ok(obj.into());
+        }
+        if let Some((tag, index)) = self.indexed_variant_tag {
+            let obj = self.new_object();
+            let obj = obj.unchecked_ref::<ObjectExt>();
+            obj.set(
+                &static_str_to_js(tag),
+                JsString::clone(&static_str_to_js(self.variant_case.apply(variant))).into(),
+            );
+            obj.set(&static_str_to_js(index), variant_index.into());
+            return Ok(obj.into());
+        }
+        if self.unit_variants_as_index {
+            return self.serialize_u32(variant_index);
+        }
+        Ok(JsString::clone(&static_str_to_js(self.variant_case.apply(variant))).into())
     }
 
+    /// Newtype structs (including single-field tuple structs like `struct One(i32)`) are
+    /// transparent and serialise as their inner value, same as in serde-json.
+    ///
+    /// [`PreservedValue`](crate::PreservedValue) is recognised by its name and stored as-is.
     fn serialize_newtype_struct<T: ?Sized + Serialize>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result {
+        if name == PRESERVED_VALUE_MAGIC {
+            return take_handover().ok_or_else(|| {
+                Error::custom("only PreservedValue can use its reserved newtype struct name")
+            });
+        }
         value.serialize(self)
     }
 
     fn serialize_newtype_variant<T: ?Sized + Serialize>(
         self,
         _name: &'static str,
-        _variant_index: u32,
+        variant_index: u32,
         variant: &'static str,
         value: &T,
     ) -> Result {
-        VariantSerializer::new(variant, self.serialize_newtype_struct(variant, value)?).end(Ok)
+        if self.raw_variant == Some(variant) {
+            return value.serialize(self);
+        }
+        self.variant_serializer(
+            variant_index,
+            variant,
+            self.serialize_newtype_struct(variant, value)?,
+        )
+        .end(Ok)
     }
 
     /// Serialises any Rust iterable into a JS Array.
     // TODO: Figure out if there is a way to detect and serialise `Set` differently.
-    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
-        Ok(ArraySerializer::new(self))
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(ArraySerializer::new(self, len))
     }
 
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
@@ -429,11 +1189,12 @@ impl<'s> ser::Serializer for &'s Serializer {
     fn serialize_tuple_variant(
         self,
         _name: &'static str,
-        _variant_index: u32,
+        variant_index: u32,
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        Ok(VariantSerializer::new(
+        Ok(self.variant_serializer(
+            variant_index,
             variant,
             self.serialize_tuple_struct(variant, len)?,
         ))
@@ -445,20 +1206,515 @@ impl<'s> ser::Serializer for &'s Serializer {
     }
 
     /// Serialises Rust typed structs into plain JS objects.
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        let serializer = ObjectSerializer::new(self);
+        if self.annotate_types {
+            let descriptor = self.new_object();
+            descriptor.unchecked_ref::<ObjectExt>().set(
+                &static_str_to_js("value"),
+                JsString::clone(&static_str_to_js(name)).into(),
+            );
+            Object::define_property(
+                serializer.target.unchecked_ref::<Object>(),
+                &static_str_to_js("$rustType"),
+                &descriptor,
+            );
+        }
+        Ok(serializer)
+    }
+
+    /// The payload object is annotated with the enum name when `annotate_types` is enabled,
+    /// and gets the variant name (and index) as fields instead of being wrapped with
+    /// `indexed_variant_tag` or `struct_variant_tag`.
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        let mut payload = self.serialize_struct(name, len)?;
+        if self.adjacent_variant_tag.is_none() {
+            if let Some((tag, index)) = self.indexed_variant_tag {
+                let variant = self.variant_case.apply(variant);
+                ser::SerializeStruct::serialize_field(&mut payload, tag, variant)?;
+                ser::SerializeStruct::serialize_field(&mut payload, index, &variant_index)?;
+                return Ok(VariantSerializer::new(None, payload));
+            }
+            if let Some(tag) = self.struct_variant_tag {
+                let variant = self.variant_case.apply(variant);
+                ser::SerializeStruct::serialize_field(&mut payload, tag, variant)?;
+                return Ok(VariantSerializer::new(None, payload));
+            }
+        }
+        Ok(self.variant_serializer(variant_index, variant, payload))
+    }
+}
+
+/// Serializes a top-level sequence by passing each element to a JS callback as soon as it's
+/// serialized, instead of collecting them into an `Array`.
+///
+/// Tuples and tuple structs are streamed like sequences, and newtype wrappers around them are
+/// transparent, while anything else is rejected.
+pub(crate) struct StreamingSerializer<'s> {
+    serializer: &'s Serializer,
+    callback: &'s js_sys::Function,
+}
+
+impl<'s> StreamingSerializer<'s> {
+    pub fn new(serializer: &'s Serializer, callback: &'s js_sys::Function) -> Self {
+        Self {
+            serializer,
+            callback,
+        }
+    }
+}
+
+fn not_a_sequence() -> Error {
+    Error::custom("only sequences can be serialized as a stream")
+}
+
+macro_rules! reject_with {
+    ($error:ident: $($name:ident($($ty:ty),*);)*) => {
+        $(fn $name(self, $(_: $ty),*) -> Result<()> {
+            Err($error())
+        })*
+    };
+}
+
+impl<'s> ser::Serializer for StreamingSerializer<'s> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = ser::Impossible<(), Error>;
+    type SerializeMap = ser::Impossible<(), Error>;
+    type SerializeStruct = ser::Impossible<(), Error>;
+    type SerializeStructVariant = ser::Impossible<(), Error>;
+
+    reject_with! {
+        not_a_sequence:
+
+        serialize_bool(bool);
+
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+
+        serialize_f32(f32);
+        serialize_f64(f64);
+
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<()> {
+        Err(not_a_sequence())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()> {
+        Err(not_a_sequence())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(not_a_sequence())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(not_a_sequence())
+    }
+
     fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
-        Ok(ObjectSerializer::new(self))
+        Err(not_a_sequence())
     }
 
     fn serialize_struct_variant(
         self,
         _name: &'static str,
         _variant_index: u32,
-        variant: &'static str,
-        len: usize,
+        _variant: &'static str,
+        _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        Ok(VariantSerializer::new(
-            variant,
-            self.serialize_struct(variant, len)?,
-        ))
+        Err(not_a_sequence())
+    }
+}
+
+impl ser::SerializeSeq for StreamingSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let value = value.serialize(self.serializer)?;
+        self.callback.call1(&JsValue::UNDEFINED, &value)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for StreamingSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for StreamingSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeTuple::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeTuple::end(self)
+    }
+}
+
+/// An `Array` of values of a single struct field across all rows of
+/// [`Serializer::serialize_columnar`].
+struct Column {
+    key: &'static str,
+    values: Array,
+    len: u32,
+}
+
+/// Serializes struct rows one by one, appending their fields to the corresponding columns.
+pub(crate) struct ColumnarSerializer<'s> {
+    serializer: &'s Serializer,
+    columns: Vec<Column>,
+    rows: u32,
+}
+
+impl<'s> ColumnarSerializer<'s> {
+    pub fn new(serializer: &'s Serializer) -> Self {
+        Self {
+            serializer,
+            columns: Vec::new(),
+            rows: 0,
+        }
+    }
+
+    pub fn serialize_row<T: ?Sized + Serialize>(&mut self, row: &T) -> Result<()> {
+        row.serialize(&mut *self)?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Builds the resulting object, with `undefined` for fields missing in some of the rows
+    /// (e.g. due to `#[serde(skip_serializing_if = "...")]`), so that all columns are aligned.
+    pub fn end(self) -> Result {
+        let target = self.serializer.new_object().unchecked_into::<ObjectExt>();
+        for column in self.columns {
+            for _ in column.len..self.rows {
+                column.values.push(&JsValue::UNDEFINED);
+            }
+            target.set(&static_str_to_js(column.key), column.values.into());
+        }
+        Ok(target.into())
+    }
+}
+
+fn not_a_struct() -> Error {
+    Error::custom("only structs can be serialized as columns")
+}
+
+impl<'a, 's> ser::Serializer for &'a mut ColumnarSerializer<'s> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<(), Error>;
+    type SerializeTuple = ser::Impossible<(), Error>;
+    type SerializeTupleStruct = ser::Impossible<(), Error>;
+    type SerializeTupleVariant = ser::Impossible<(), Error>;
+    type SerializeMap = ser::Impossible<(), Error>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = ser::Impossible<(), Error>;
+
+    reject_with! {
+        not_a_struct:
+
+        serialize_bool(bool);
+
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+
+        serialize_f32(f32);
+        serialize_f64(f64);
+
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<()> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(not_a_struct())
+    }
+}
+
+impl ser::SerializeStruct for &mut ColumnarSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        let value = value.serialize(self.serializer)?;
+        // Fields usually come in the same order in every row, so this is mostly a direct hit.
+        let column = match self.columns.iter().position(|column| column.key == key) {
+            Some(index) => &mut self.columns[index],
+            None => {
+                self.columns.push(Column {
+                    key,
+                    values: Array::new(),
+                    len: 0,
+                });
+                self.columns.last_mut().unwrap()
+            }
+        };
+        for _ in column.len..self.rows {
+            column.values.push(&JsValue::UNDEFINED);
+        }
+        column.values.push(&value);
+        column.len = self.rows + 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Serializes map keys like the wrapped [`Serializer`], but looks up strings in its
+/// [`Serializer::cache_map_keys`] cache first, including ones wrapped into newtypes.
+struct MapKeySerializer<'s> {
+    serializer: &'s Serializer,
+    cache: &'s RefCell<fnv::FnvHashMap<String, JsValue>>,
+}
+
+macro_rules! forward_to_serializer {
+    ($($name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(fn $name(self, $($arg: $ty),*) -> Result<$ret> {
+            self.serializer.$name($($arg),*)
+        })*
+    };
+}
+
+impl<'s> ser::Serializer for MapKeySerializer<'s> {
+    type Ok = JsValue;
+    type Error = Error;
+
+    type SerializeSeq = ArraySerializer<'s>;
+    type SerializeTuple = ArraySerializer<'s>;
+    type SerializeTupleStruct = ArraySerializer<'s>;
+    type SerializeTupleVariant = VariantSerializer<ArraySerializer<'s>>;
+    type SerializeMap = MapSerializer<'s>;
+    type SerializeStruct = ObjectSerializer<'s>;
+    type SerializeStructVariant = VariantSerializer<ObjectSerializer<'s>>;
+
+    forward_to_serializer! {
+        serialize_bool(v: bool) -> JsValue;
+
+        serialize_i8(v: i8) -> JsValue;
+        serialize_i16(v: i16) -> JsValue;
+        serialize_i32(v: i32) -> JsValue;
+        serialize_i64(v: i64) -> JsValue;
+        serialize_i128(v: i128) -> JsValue;
+
+        serialize_u8(v: u8) -> JsValue;
+        serialize_u16(v: u16) -> JsValue;
+        serialize_u32(v: u32) -> JsValue;
+        serialize_u64(v: u64) -> JsValue;
+        serialize_u128(v: u128) -> JsValue;
+
+        serialize_f32(v: f32) -> JsValue;
+        serialize_f64(v: f64) -> JsValue;
+
+        serialize_char(v: char) -> JsValue;
+        serialize_bytes(v: &[u8]) -> JsValue;
+
+        serialize_none() -> JsValue;
+        serialize_unit() -> JsValue;
+        serialize_unit_struct(name: &'static str) -> JsValue;
+        serialize_unit_variant(name: &'static str, variant_index: u32, variant: &'static str) -> JsValue;
+
+        serialize_seq(len: Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(len: usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Self::SerializeTupleVariant;
+        serialize_map(len: Option<usize>) -> Self::SerializeMap;
+        serialize_struct(name: &'static str, len: usize) -> Self::SerializeStruct;
+        serialize_struct_variant(name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Self::SerializeStructVariant;
+    }
+
+    fn serialize_str(self, v: &str) -> Result {
+        let mut cache = self.cache.borrow_mut();
+        if let Some(key) = cache.get(v) {
+            return Ok(key.clone());
+        }
+        let key = JsValue::from(v);
+        cache.insert(v.to_owned(), key.clone());
+        Ok(key)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result {
+        self.serializer.serialize_some(value)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result {
+        if name == PRESERVED_VALUE_MAGIC {
+            return self.serializer.serialize_newtype_struct(name, value);
+        }
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result {
+        self.serializer
+            .serialize_newtype_variant(name, variant_index, variant, value)
     }
 }
@@ -0,0 +1,20 @@
+use serde::ser;
+
+use super::PreservedValue;
+
+/// A slice of `f32`s that is serialized as a `Float32Array` instead of an `Array` of numbers.
+///
+/// It's passed through as a [`PreservedValue`], so it composes with any container, e.g. as
+/// a value of a `HashMap<String, Float32Slice>`, and has the same limitations with other
+/// serializers. The resulting array is a copy, so it stays valid after the slice is gone.
+///
+/// Any JavaScript iterable of numbers, including `Float32Array`, can be deserialized back
+/// into a `Vec<f32>`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Float32Slice<'a>(pub &'a [f32]);
+
+impl ser::Serialize for Float32Slice<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PreservedValue(js_sys::Float32Array::from(self.0).into()).serialize(serializer)
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{de, ser};
+use std::fmt;
+
+/// An unsigned integer that is serialized as a decimal string zero-padded to at least `N` digits,
+/// e.g. `ZeroPadded::<4>(42)` becomes `"0042"`, for JavaScript APIs expecting fixed-width fields.
+///
+/// Values with more than `N` digits are never truncated: `ZeroPadded::<4>(12345)` becomes `"12345"`.
+///
+/// Deserializes from a string of decimal digits, ignoring any leading zeros, so both `"0042"` and
+/// `"42"` give `42` regardless of `N`. Signs, whitespace and other characters are rejected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ZeroPadded<const N: usize>(pub u64);
+
+impl<const N: usize> From<u64> for ZeroPadded<N> {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl<const N: usize> From<ZeroPadded<N>> for u64 {
+    fn from(value: ZeroPadded<N>) -> Self {
+        value.0
+    }
+}
+
+impl<const N: usize> ser::Serialize for ZeroPadded<N> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:01$}", self.0, N))
+    }
+}
+
+impl<'de, const N: usize> de::Deserialize<'de> for ZeroPadded<N> {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor<const N: usize>;
+
+        impl<'de, const N: usize> de::Visitor<'de> for Visitor<N> {
+            type Value = ZeroPadded<N>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string of decimal digits")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                // `u64::from_str` would also accept a leading `+`.
+                if v.is_empty() || !v.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(de::Error::invalid_value(de::Unexpected::Str(v), &self));
+                }
+                v.parse().map(ZeroPadded).map_err(|_| {
+                    de::Error::invalid_value(de::Unexpected::Str(v), &"a number within u64 range")
+                })
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
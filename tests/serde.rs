@@ -1,12 +1,21 @@
-use js_sys::BigInt;
+// Existing tests still use the legacy `std::i64::MAX`-style constants, and `bytes` modifies
+// its source buffer only to check that the serialized value is a copy.
+#![allow(clippy::legacy_numeric_constants, unused_assignments)]
+
+use indexmap::IndexMap;
+use js_sys::{Array, BigInt, Object, Reflect};
 use maplit::{btreemap, hashmap, hashset};
 use serde::de::DeserializeOwned;
 use serde::ser::Error as SerError;
 use serde::{Deserialize, Serialize};
-use serde_wasm_bindgen::{from_value, to_value, Error, Serializer};
+use serde_wasm_bindgen::{
+    from_columnar, from_value, to_columnar, to_value, BoolEnum, Case, Deserializer, Error,
+    MissingMapValues, PreservedValue, Serializer, ZeroPadded,
+};
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::time::Duration;
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_test::*;
 
@@ -143,6 +152,26 @@ fn bool() {
     test_primitive(true);
 }
 
+#[wasm_bindgen_test]
+fn bools_as_numbers() {
+    let serializer = Serializer::new().bools_as_numbers(true);
+    test_via_into_with_config(false, 0, &serializer);
+    test_via_into_with_config(true, 1, &serializer);
+    let value = vec![true, false].serialize(&serializer).unwrap();
+    assert_eq!(js_sys::JSON::stringify(&value).unwrap(), "[1,0]");
+    assert_eq!(from_value::<Vec<bool>>(value).unwrap(), [true, false]);
+
+    // Booleans are still the default and are accepted either way.
+    assert_eq!(to_value(&true).unwrap(), true);
+    assert!(from_value::<bool>(JsValue::TRUE).unwrap());
+
+    // Only `0` and `1` are accepted as numbers.
+    for &v in &[2.0, -1.0, 0.5, f64::NAN] {
+        from_value::<bool>(JsValue::from(v)).unwrap_err();
+    }
+    from_value::<bool>(JsValue::from("1")).unwrap_err();
+}
+
 #[wasm_bindgen_test]
 fn numbers() {
     test_signed!(i8);
@@ -318,6 +347,154 @@ fn numbers() {
     }
 }
 
+#[wasm_bindgen_test]
+fn integers_from_numbers() {
+    const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+    assert_eq!(
+        from_value::<i64>(JsValue::from(MAX_SAFE_INTEGER)).unwrap(),
+        9_007_199_254_740_991
+    );
+    assert_eq!(
+        from_value::<i64>(JsValue::from(-MAX_SAFE_INTEGER)).unwrap(),
+        -9_007_199_254_740_991
+    );
+    assert_eq!(
+        from_value::<u64>(JsValue::from(MAX_SAFE_INTEGER)).unwrap(),
+        9_007_199_254_740_991
+    );
+    assert_eq!(from_value::<i32>(JsValue::from(-0.0)).unwrap(), 0);
+    assert_eq!(from_value::<u8>(JsValue::from(-0.0)).unwrap(), 0);
+
+    for &v in &[
+        MAX_SAFE_INTEGER + 1.0,
+        -(MAX_SAFE_INTEGER + 1.0),
+        0.5,
+        -1.5,
+        f64::NAN,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+    ] {
+        from_value::<i64>(JsValue::from(v)).unwrap_err();
+        from_value::<u64>(JsValue::from(v)).unwrap_err();
+        from_value::<i32>(JsValue::from(v)).unwrap_err();
+    }
+    from_value::<u64>(JsValue::from(-1.0)).unwrap_err();
+
+    // Self-describing targets get integers only for safe integers, and floats otherwise.
+    assert!(from_value::<serde_json::Value>(JsValue::from(42.0))
+        .unwrap()
+        .is_i64());
+    assert!(from_value::<serde_json::Value>(JsValue::from(42.5))
+        .unwrap()
+        .is_f64());
+    assert!(
+        from_value::<serde_json::Value>(JsValue::from(MAX_SAFE_INTEGER + 1.0))
+            .unwrap()
+            .is_f64()
+    );
+}
+
+#[wasm_bindgen_test]
+fn integers_range_errors() {
+    fn error<T: DeserializeOwned + Debug>(v: f64) -> String {
+        from_value::<T>(JsValue::from(v)).unwrap_err().to_string()
+    }
+
+    // Fractional numbers are never truncated.
+    assert_eq!(
+        error::<u32>(3.5),
+        "SerdeWasmBindgenError: invalid value: 3.5 is not an integer, expected u32"
+    );
+    assert_eq!(
+        error::<i8>(-0.25),
+        "SerdeWasmBindgenError: invalid value: -0.25 is not an integer, expected i8"
+    );
+    assert_eq!(
+        error::<i64>(f64::NAN),
+        "SerdeWasmBindgenError: invalid value: NaN is not an integer, expected i64"
+    );
+
+    // Overflows name the target type.
+    assert_eq!(
+        error::<u32>(4294967296.0),
+        "SerdeWasmBindgenError: invalid value: 4294967296 is out of range for u32"
+    );
+    assert_eq!(
+        error::<i16>(-32769.0),
+        "SerdeWasmBindgenError: invalid value: -32769 is out of range for i16"
+    );
+    assert_eq!(
+        error::<u8>(f64::INFINITY),
+        "SerdeWasmBindgenError: invalid value: inf is out of range for u8"
+    );
+    assert_eq!(
+        error::<u64>(2f64.powi(53)),
+        "SerdeWasmBindgenError: invalid value: 9007199254740992 is not a safe integer, expected u64"
+    );
+
+    // Negative numbers don't fit into unsigned types.
+    assert_eq!(
+        error::<u8>(-1.0),
+        "SerdeWasmBindgenError: invalid value: -1 is out of range for u8"
+    );
+    assert_eq!(
+        error::<u64>(-42.0),
+        "SerdeWasmBindgenError: invalid value: -42 is out of range for u64"
+    );
+
+    // Bounds themselves are fine.
+    assert_eq!(
+        from_value::<u32>(JsValue::from(4294967295.0)).unwrap(),
+        u32::MAX
+    );
+    assert_eq!(
+        from_value::<i16>(JsValue::from(-32768.0)).unwrap(),
+        i16::MIN
+    );
+    assert_eq!(from_value::<u8>(JsValue::from(-0.0)).unwrap(), 0);
+
+    // Non-numbers are still reported as invalid types.
+    let error = from_value::<u32>(JsValue::from("42"))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("invalid type"), "{}", error);
+}
+
+#[wasm_bindgen_test]
+fn f32_as_shortest_decimal() {
+    let serializer = Serializer::new().serialize_f32_as_shortest_decimal(true);
+
+    // By default `f32` is widened with all the binary representation artifacts.
+    assert_eq!(to_value(&0.1_f32).unwrap(), 0.1_f32 as f64);
+    assert_ne!(to_value(&0.1_f32).unwrap(), 0.1_f64);
+
+    for &(v, expected) in &[
+        (0.1_f32, 0.1_f64),
+        (0.2, 0.2),
+        (0.3, 0.3),
+        (-1.1, -1.1),
+        (2.5, 2.5),
+        (9.99, 9.99),
+        (0.001, 0.001),
+        (123.456, 123.456),
+        (1e-40, 1e-40),
+        (f32::MAX, 3.4028235e38),
+        (0.0, 0.0),
+    ] {
+        test_via_into_with_config(v, expected, &serializer);
+    }
+
+    test_via_into_with_config(f32::INFINITY, f64::INFINITY, &serializer);
+    test_via_into_with_config(f32::NEG_INFINITY, f64::NEG_INFINITY, &serializer);
+    assert!(f32::NAN
+        .serialize(&serializer)
+        .unwrap()
+        .as_f64()
+        .unwrap()
+        .is_nan());
+}
+
 #[wasm_bindgen_test]
 fn strings() {
     fn test_str(s: &'static str) {
@@ -340,6 +517,24 @@ fn chars() {
     test_via_into('😃', "😃");
 }
 
+#[wasm_bindgen_test]
+fn chars_as_numbers() {
+    let serializer = Serializer::new().serialize_char_as_number(true);
+
+    test_via_into_with_config('a', 97, &serializer);
+    test_via_into_with_config('\0', 0, &serializer);
+    test_via_into_with_config('😃', 0x1F603, &serializer);
+
+    // Strings are still the default and are accepted either way.
+    assert_eq!(to_value(&'a').unwrap(), "a");
+    assert_eq!(from_value::<char>(JsValue::from("a")).unwrap(), 'a');
+
+    // Surrogates, values outside of the Unicode range and non-integers are rejected.
+    for &v in &[0xD800 as f64, 0x110000 as f64, -1.0, 97.5] {
+        from_value::<char>(JsValue::from(v)).unwrap_err();
+    }
+}
+
 #[wasm_bindgen_test]
 fn bytes() {
     // Create a backing storage.
@@ -364,6 +559,94 @@ fn bytes() {
     assert_eq!(deserialized.as_ref(), orig_src);
 }
 
+#[wasm_bindgen_test]
+fn sealed_bytes() {
+    let bytes = serde_bytes::Bytes::new(&[1, 2, 3]);
+
+    let value = to_value(&bytes).unwrap();
+    assert!(!Object::is_sealed(value.unchecked_ref::<Object>()));
+
+    let serializer = Serializer::new().seal_byte_arrays(true);
+    let value = bytes.serialize(&serializer).unwrap();
+    let array = value.dyn_ref::<js_sys::Uint8Array>().unwrap();
+    // Whether `Object.seal` succeeds on non-empty typed arrays depends on the engine, but
+    // they end up non-extensible either way.
+    assert!(!Object::is_extensible(array));
+    assert!(!js_sys::Reflect::define_property(
+        array,
+        &"extra".into(),
+        &js_sys::JSON::parse(r#"{"value": 1}"#)
+            .unwrap()
+            .unchecked_into()
+    )
+    .unwrap());
+    // Elements of non-empty typed arrays can't be made read-only.
+    assert!(!Object::is_frozen(array));
+    array.set_index(0, 10);
+    assert_eq!(array.get_index(0), 10);
+
+    // Empty typed arrays can be fully frozen.
+    let value = serde_bytes::Bytes::new(&[]).serialize(&serializer).unwrap();
+    assert!(Object::is_frozen(value.unchecked_ref::<Object>()));
+
+    let deserialized: serde_bytes::ByteBuf = from_value(value).unwrap();
+    assert!(deserialized.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn large_bytes() {
+    const LEN: u32 = 64 << 20;
+
+    fn memory_size() -> u32 {
+        wasm_bindgen::memory()
+            .unchecked_into::<js_sys::WebAssembly::Memory>()
+            .buffer()
+            .unchecked_into::<js_sys::ArrayBuffer>()
+            .byte_length()
+    }
+
+    let src = js_sys::Uint8Array::new_with_length(LEN);
+    src.set_index(LEN - 1, 42);
+
+    let before = memory_size();
+    let deserialized: serde_bytes::ByteBuf = from_value(src.into()).unwrap();
+    let growth = memory_size() - before;
+
+    assert_eq!(deserialized.len(), LEN as usize);
+    assert_eq!(deserialized[LEN as usize - 1], 42);
+    // The buffer is copied straight into a single allocation of the final size, without
+    // any temporary copies or reallocations along the way that would double the peak usage.
+    assert!(growth <= LEN + LEN / 8, "memory grew by {} bytes", growth);
+}
+
+#[wasm_bindgen_test]
+fn bytes_as_dataview() {
+    let bytes = serde_bytes::Bytes::new(&[1, 2, 3]);
+
+    assert!(to_value(&bytes)
+        .unwrap()
+        .is_instance_of::<js_sys::Uint8Array>());
+
+    let serializer = Serializer::new().bytes_as_dataview(true);
+    let value = bytes.serialize(&serializer).unwrap();
+    let view = value.dyn_ref::<js_sys::DataView>().unwrap();
+    assert_eq!(view.byte_offset(), 0);
+    assert_eq!(view.byte_length(), 3);
+    assert_eq!(view.buffer().byte_length(), 3);
+    assert_eq!(view.get_uint8(2), 3);
+
+    let deserialized: serde_bytes::ByteBuf = from_value(value.clone()).unwrap();
+    assert_eq!(deserialized.as_ref(), [1, 2, 3]);
+    let deserialized: serde_json::Value = from_value(value).unwrap();
+    assert_eq!(deserialized, serde_json::json!([1, 2, 3]));
+
+    // Only the bytes within the view are read.
+    let buffer = js_sys::Uint8Array::from(&[1, 2, 3, 4, 5][..]).buffer();
+    let view = js_sys::DataView::new(&buffer, 1, 3);
+    let deserialized: serde_bytes::ByteBuf = from_value(view.into()).unwrap();
+    assert_eq!(deserialized.as_ref(), [2, 3, 4]);
+}
+
 #[wasm_bindgen_test]
 fn options() {
     test_via_into(Some(0_u32), 0_u32);
@@ -382,6 +665,92 @@ fn options() {
     assert_eq!(to_value(&Some(None::<()>)).unwrap(), JsValue::UNDEFINED);
 }
 
+#[wasm_bindgen_test]
+fn nested_options() {
+    #[derive(Debug, PartialEq, Serialize)]
+    struct Patch {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<Option<String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        age: Option<Option<u8>>,
+    }
+
+    let serializer = Serializer::new().serialize_some_none_as_null(true);
+    let value = Patch {
+        name: Some(None),
+        age: None,
+    }
+    .serialize(&serializer)
+    .unwrap();
+    assert_eq!(Reflect::get(&value, &"name".into()).unwrap(), JsValue::NULL);
+    assert!(!Reflect::has(&value, &"age".into()).unwrap());
+    assert_eq!(js_sys::JSON::stringify(&value).unwrap(), r#"{"name":null}"#);
+
+    let value = Patch {
+        name: Some(Some("x".to_string())),
+        age: Some(Some(1)),
+    }
+    .serialize(&serializer)
+    .unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"name":"x","age":1}"#
+    );
+
+    // Plain `None` is unaffected, including inside collections.
+    assert_eq!(
+        None::<Option<u8>>.serialize(&serializer).unwrap(),
+        JsValue::UNDEFINED
+    );
+    assert_eq!(
+        Some(None::<u8>).serialize(&serializer).unwrap(),
+        JsValue::NULL
+    );
+    let value = Some(vec![None::<u8>]).serialize(&serializer).unwrap();
+    assert_eq!(Array::from(&value).get(0), JsValue::UNDEFINED);
+}
+
+#[wasm_bindgen_test]
+fn top_level_options() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    // `Some` doesn't add a layer on top of the value, however deep it is.
+    let point = || Point { x: 1, y: 2 };
+    for value in [
+        to_value(&Some(point())).unwrap(),
+        to_value(&Some(Some(point()))).unwrap(),
+        to_value(&Some(Some(Some(point())))).unwrap(),
+    ] {
+        assert_eq!(js_sys::JSON::stringify(&value).unwrap(), r#"{"x":1,"y":2}"#);
+        assert_eq!(from_value::<Option<Point>>(value).unwrap(), Some(point()));
+    }
+    assert_json(to_value(&Some(vec![1, 2])).unwrap(), vec![1, 2]);
+
+    test_via_into(None::<Point>, JsValue::UNDEFINED);
+    assert_eq!(from_value::<Option<Point>>(JsValue::NULL).unwrap(), None);
+    let serializer = Serializer::new().serialize_missing_as_null(true);
+    test_via_into_with_config(None::<Point>, JsValue::NULL, &serializer);
+    assert_json(Some(point()).serialize(&serializer).unwrap(), point());
+
+    test_via_into(Some(Some("a".to_string())), "a");
+    test_via_into(None::<Option<String>>, JsValue::UNDEFINED);
+    // `Some(None)` and `None` are indistinguishable by default.
+    assert_eq!(to_value(&Some(None::<String>)).unwrap(), JsValue::UNDEFINED);
+
+    // With `serialize_some_none_as_null`, they serialize differently, but only `None` can be
+    // restored, as `null` is treated as a missing value.
+    let serializer = Serializer::new().serialize_some_none_as_null(true);
+    test_via_into_with_config(Some(Some("a".to_string())), "a", &serializer);
+    test_via_into_with_config(None::<Option<String>>, JsValue::UNDEFINED, &serializer);
+    let value = Some(None::<String>).serialize(&serializer).unwrap();
+    assert_eq!(value, JsValue::NULL);
+    assert_eq!(from_value::<Option<Option<String>>>(value).unwrap(), None);
+}
+
 #[wasm_bindgen_test]
 fn enums() {
     test_enum! {
@@ -436,137 +805,2547 @@ fn enums() {
 }
 
 #[wasm_bindgen_test]
-fn structs() {
+fn enums_adjacently_tagged() {
     #[derive(Debug, PartialEq, Serialize, Deserialize)]
-    struct Unit;
+    #[serde(tag = "t", content = "c")]
+    enum Message {
+        Ping,
+        Text(String),
+        Move(i32, i32),
+        Resize { w: u32, h: u32 },
+    }
 
-    test_via_into(Unit, JsValue::UNDEFINED);
+    // Content is the inner value itself, without extra `{ Variant: ... }` wrapping,
+    // regardless of options affecting externally tagged enums.
+    let serializers = [
+        Serializer::new(),
+        Serializer::new().variant_keys_as_index(true),
+        Serializer::new().struct_variant_tag(Some("type")),
+    ];
+    for serializer in &serializers {
+        for (value, json) in [
+            (Message::Ping, r#"{"t":"Ping"}"#),
+            (Message::Text("hi".to_string()), r#"{"t":"Text","c":"hi"}"#),
+            (Message::Move(1, -2), r#"{"t":"Move","c":[1,-2]}"#),
+            (
+                Message::Resize { w: 3, h: 4 },
+                r#"{"t":"Resize","c":{"w":3,"h":4}}"#,
+            ),
+        ] {
+            assert_eq!(serde_json::to_string(&value).unwrap(), json);
+            let serialized = value.serialize(serializer).unwrap();
+            assert_eq!(js_sys::JSON::stringify(&serialized).unwrap(), json);
+            assert_eq!(from_value::<Message>(serialized).unwrap(), value);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn enums_variant_case() {
+    fn to_json(value: &impl Serialize) -> String {
+        serde_json::to_string(value).unwrap()
+    }
 
     #[derive(Debug, PartialEq, Serialize, Deserialize)]
-    struct Newtype<A>(A);
+    enum Status {
+        Active,
+        InProgress,
+        OnHold(u32),
+        NeedsReview { by: String },
+    }
 
-    test_via_json(Newtype("newtype content".to_string()));
+    // Same names as `rename_all` produces.
+    #[derive(Serialize)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    enum ScreamingStatus {
+        Active,
+        InProgress,
+        OnHold(u32),
+        NeedsReview { by: String },
+    }
 
-    #[derive(Debug, PartialEq, Serialize, Deserialize)]
-    struct Tuple<A, B>(A, B);
+    #[derive(Serialize)]
+    #[serde(rename_all = "kebab-case")]
+    enum KebabStatus {
+        Active,
+        InProgress,
+        OnHold(u32),
+        NeedsReview { by: String },
+    }
 
-    test_via_json(Tuple("tuple content".to_string(), 42));
+    let review = || "alice".to_string();
+    for (case, values) in [
+        (
+            Case::ScreamingSnake,
+            vec![
+                (Status::Active, to_json(&ScreamingStatus::Active)),
+                (Status::InProgress, to_json(&ScreamingStatus::InProgress)),
+                (Status::OnHold(1), to_json(&ScreamingStatus::OnHold(1))),
+                (
+                    Status::NeedsReview { by: review() },
+                    to_json(&ScreamingStatus::NeedsReview { by: review() }),
+                ),
+            ],
+        ),
+        (
+            Case::Kebab,
+            vec![
+                (Status::Active, to_json(&KebabStatus::Active)),
+                (Status::InProgress, to_json(&KebabStatus::InProgress)),
+                (Status::OnHold(1), to_json(&KebabStatus::OnHold(1))),
+                (
+                    Status::NeedsReview { by: review() },
+                    to_json(&KebabStatus::NeedsReview { by: review() }),
+                ),
+            ],
+        ),
+    ] {
+        let serializer = Serializer::new().variant_case(case);
+        let deserialize =
+            |value: JsValue| Status::deserialize(Deserializer::from(value).variant_case(case));
+        for (value, json) in values {
+            let serialized = value.serialize(&serializer).unwrap();
+            assert_eq!(
+                String::from(js_sys::JSON::stringify(&serialized).unwrap()),
+                json
+            );
+            assert_eq!(deserialize(serialized.clone()).unwrap(), value);
+            // The original names are still accepted.
+            assert_eq!(deserialize(to_value(&value).unwrap()).unwrap(), value);
+            // But converted ones require opting in.
+            if value != Status::Active {
+                from_value::<Status>(serialized).unwrap_err();
+            }
+        }
+    }
 
-    #[derive(Debug, PartialEq, Serialize, Deserialize)]
-    struct Struct<A, B> {
-        a: A,
-        b: B,
+    for (case, name) in [
+        (Case::Unchanged, "InProgress"),
+        (Case::Lower, "inprogress"),
+        (Case::Upper, "INPROGRESS"),
+        (Case::Camel, "inProgress"),
+        (Case::Snake, "in_progress"),
+        (Case::ScreamingSnake, "IN_PROGRESS"),
+        (Case::Kebab, "in-progress"),
+        (Case::ScreamingKebab, "IN-PROGRESS"),
+    ] {
+        let serializer = Serializer::new().variant_case(case);
+        assert_eq!(Status::InProgress.serialize(&serializer).unwrap(), name);
+        let deserializer = Deserializer::from(JsValue::from(name)).variant_case(case);
+        assert_eq!(
+            Status::deserialize(deserializer).unwrap(),
+            Status::InProgress
+        );
     }
 
-    test_via_json(Struct {
-        a: "struct content".to_string(),
-        b: 42,
-    });
+    // Tags of other representations are converted too.
+    let serializer = Serializer::new()
+        .variant_case(Case::Snake)
+        .struct_variant_tag(Some("type"));
+    let value = Status::NeedsReview { by: review() }
+        .serialize(&serializer)
+        .unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"type":"needs_review","by":"alice"}"#
+    );
+    let deserializer = Deserializer::from(value)
+        .variant_case(Case::Snake)
+        .struct_variant_tag(Some("type"));
+    assert_eq!(
+        Status::deserialize(deserializer).unwrap(),
+        Status::NeedsReview { by: review() }
+    );
+
+    let keys = Some(("__typename", "data"));
+    let serializer = Serializer::new()
+        .variant_case(Case::Camel)
+        .adjacent_variant_tag(keys);
+    let value = Status::OnHold(1).serialize(&serializer).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"__typename":"onHold","data":1}"#
+    );
+    let deserializer = Deserializer::from(value)
+        .variant_case(Case::Camel)
+        .adjacent_variant_tag(keys);
+    assert_eq!(
+        Status::deserialize(deserializer).unwrap(),
+        Status::OnHold(1)
+    );
 }
 
 #[wasm_bindgen_test]
-fn sequences() {
-    test_via_json([1, 2]);
-    test_via_json(["".to_string(), "x".to_string(), "xyz".to_string()]);
-    test_via_json((100, "xyz".to_string(), true));
+fn enums_case_insensitive_variants() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Shape {
+        Circle { radius: u32 },
+        Square { side: u32 },
+        Empty,
+    }
 
-    // Sets are currently indistinguishable from other sequences for
-    // Serde serialisers, so this will become an array on the JS side.
-    test_via_json(hashset! {false, true});
+    let parse = |json: &str| js_sys::JSON::parse(json).unwrap();
+    let deserializer = |json: &str| {
+        Deserializer::from(parse(json))
+            .struct_variant_tag(Some("type"))
+            .case_insensitive_variants(true)
+    };
+
+    // The tag is read from the object, matched ignoring case, and the rest are the fields.
+    for json in &[
+        r#"{"type":"circle","radius":1}"#,
+        r#"{"type":"CIRCLE","radius":1}"#,
+        r#"{"radius":1,"type":"cIrClE"}"#,
+        r#"{"type":"Circle","radius":1}"#,
+    ] {
+        assert_eq!(
+            Shape::deserialize(deserializer(json)).unwrap(),
+            Shape::Circle { radius: 1 }
+        );
+    }
+    assert_eq!(
+        Shape::deserialize(deserializer(r#"{"type":"square","side":2}"#)).unwrap(),
+        Shape::Square { side: 2 }
+    );
+    Shape::deserialize(deserializer(r#"{"type":"circles","radius":1}"#)).unwrap_err();
+
+    // Other representations are matched ignoring case as well.
+    assert_eq!(
+        Shape::deserialize(deserializer(r#""EMPTY""#)).unwrap(),
+        Shape::Empty
+    );
+    assert_eq!(
+        Shape::deserialize(deserializer(r#"{"square":{"side":3}}"#)).unwrap(),
+        Shape::Square { side: 3 }
+    );
+
+    // Case is respected by default.
+    let value = parse(r#"{"type":"circle","radius":1}"#);
+    Shape::deserialize(Deserializer::from(value).struct_variant_tag(Some("type"))).unwrap_err();
+
+    // Exact matches take precedence over variants differing only in case.
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Letter {
+        A,
+        #[serde(rename = "a")]
+        LowerA,
+        B,
+    }
+
+    for (json, letter) in [
+        (r#""A""#, Letter::A),
+        (r#""a""#, Letter::LowerA),
+        (r#""b""#, Letter::B),
+    ] {
+        assert_eq!(Letter::deserialize(deserializer(json)).unwrap(), letter);
+    }
+
+    // Serde reads tags of `#[serde(tag = "...")]` enums on its own, so they are still exact.
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(tag = "type")]
+    enum TaggedShape {
+        Circle { radius: u32 },
+    }
+
+    assert_eq!(
+        TaggedShape::deserialize(deserializer(r#"{"type":"Circle","radius":1}"#)).unwrap(),
+        TaggedShape::Circle { radius: 1 }
+    );
+    TaggedShape::deserialize(deserializer(r#"{"type":"circle","radius":1}"#)).unwrap_err();
+
+    // Such enums can accept other spellings of their tags via serde aliases instead.
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(tag = "type")]
+    enum AliasedShape {
+        #[serde(alias = "circle")]
+        Circle { radius: u32 },
+    }
+
+    assert_eq!(
+        AliasedShape::deserialize(deserializer(r#"{"type":"circle","radius":1}"#)).unwrap(),
+        AliasedShape::Circle { radius: 1 }
+    );
 }
 
 #[wasm_bindgen_test]
-fn maps() {
-    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
-    struct Struct<A, B> {
-        a: A,
-        b: B,
+fn enums_raw_variant() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Event {
+        Click { x: i32 },
+        Key(String),
+        Close,
+        Raw(PreservedValue),
     }
 
-    // Create a Rust HashMap with non-string keys to make sure
-    // that we support real arbitrary maps.
-    let src = hashmap! {
-        Struct {
-            a: 1,
-            b: "smth".to_string(),
-        } => Struct {
-            a: 2,
-            b: "SMTH".to_string(),
-        },
-        Struct {
-            a: 42,
-            b: "something".to_string(),
-        } => Struct {
-            a: 84,
-            b: "SOMETHING".to_string(),
-        },
-    };
+    let deserializer = |value: &JsValue| Deserializer::from(value.clone()).raw_variant(Some("Raw"));
+    let serializer = Serializer::new().raw_variant(Some("Raw"));
+
+    // Unknown variants are captured as-is and written back verbatim.
+    for json in &[
+        r#"{"Scroll":{"dy":5,"path":[1,{"a":null}]}}"#,
+        r#""Resize""#,
+        "7",
+    ] {
+        let original = js_sys::JSON::parse(json).unwrap();
+        let event = Event::deserialize(deserializer(&original)).unwrap();
+        assert_eq!(event, Event::Raw(PreservedValue(original.clone())));
+        let value = event.serialize(&serializer).unwrap();
+        assert_eq!(value, original);
+        assert_eq!(js_sys::JSON::stringify(&value).unwrap(), *json);
+    }
 
-    // Convert to a JS value
-    let res = to_value(&src).unwrap();
+    // Known variants are unaffected.
+    for event in [
+        Event::Click { x: 1 },
+        Event::Key("Enter".to_string()),
+        Event::Close,
+    ] {
+        let value = event.serialize(&serializer).unwrap();
+        assert_eq!(Event::deserialize(deserializer(&value)).unwrap(), event);
+    }
+    let value = js_sys::JSON::parse(r#"{"Click":{"x":"nope"}}"#).unwrap();
+    Event::deserialize(deserializer(&value)).unwrap_err();
 
-    // Make sure that the result is an ES6 Map.
-    let res = res.dyn_into::<js_sys::Map>().unwrap();
-    assert_eq!(res.size() as usize, src.len());
+    // Works with tagged representations too, capturing the whole object.
+    let value = js_sys::JSON::parse(r#"{"type":"Scroll","dy":5}"#).unwrap();
+    let event = Event::deserialize(deserializer(&value).struct_variant_tag(Some("type"))).unwrap();
+    assert_eq!(event, Event::Raw(PreservedValue(value)));
 
-    // Compare values one by one (it's ok to use JSON for invidivual structs).
-    res.entries()
-        .into_iter()
-        .map(|kv| kv.unwrap())
-        .zip(src)
-        .for_each(|(lhs_kv, rhs_kv)| {
-            assert_json(lhs_kv, rhs_kv);
-        });
+    // Without the option, unknown variants are errors as usual.
+    let value = JsValue::from("Resize");
+    Event::deserialize(Deserializer::from(value)).unwrap_err();
 }
 
 #[wasm_bindgen_test]
-fn maps_objects_string_key() {
-    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
-    struct Struct<A, B> {
-        a: A,
-        b: B,
+fn enums_unit_variants_as_index() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Color {
+        Red,
+        Green,
+        Blue,
     }
 
-    let src = hashmap! {
-        "a".to_string() => Struct {
-            a: 2,
-            b: "S".to_string(),
-        },
-        "b".to_string() => Struct {
-            a: 3,
-            b: "T".to_string(),
-        },
-    };
+    let serializer = Serializer::new().unit_variants_as_index(true);
+    test_via_into_with_config(Color::Red, 0, &serializer);
+    test_via_into_with_config(Color::Green, 1, &serializer);
+    test_via_into_with_config(Color::Blue, 2, &serializer);
+    let value = vec![Color::Blue, Color::Red]
+        .serialize(&serializer)
+        .unwrap();
+    assert_eq!(js_sys::JSON::stringify(&value).unwrap(), "[2,0]");
+    assert_eq!(
+        from_value::<Vec<Color>>(value).unwrap(),
+        [Color::Blue, Color::Red]
+    );
 
-    test_via_json_with_config(src, Serializer::new().serialize_maps_as_objects(true));
+    // Names are still supported and used by default.
+    test_via_into(Color::Green, "Green");
+    from_value::<Color>(JsValue::from(3)).unwrap_err();
+    from_value::<Color>(JsValue::from(-1)).unwrap_err();
+    from_value::<Color>(JsValue::from(0.5)).unwrap_err();
 }
 
 #[wasm_bindgen_test]
-fn serialize_json_compatible() {
-    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
-    struct Struct {
-        a: HashMap<String, ()>,
-        b: Option<i32>,
+fn enums_struct_variant_tag() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Point { x: i32, y: i32 },
+        Rect { w: i32, h: i32, label: String },
+        Circle(f64),
+        Empty,
     }
-    let x = Struct {
-        a: hashmap! {
-            "foo".to_string() => (),
-            "bar".to_string() => (),
-        },
-        b: None,
+
+    // Same shape serde derives for internally tagged enums.
+    #[derive(Serialize)]
+    #[serde(tag = "type")]
+    enum TaggedShape {
+        Point { x: i32, y: i32 },
+        Rect { w: i32, h: i32, label: String },
+    }
+
+    let serializer = Serializer::new().struct_variant_tag(Some("type"));
+    let deserialize = |value: JsValue| {
+        Shape::deserialize(Deserializer::from(value).struct_variant_tag(Some("type")))
     };
-    test_via_json_with_config(x, Serializer::json_compatible());
+    for (value, tagged) in [
+        (
+            Shape::Point { x: 1, y: 2 },
+            TaggedShape::Point { x: 1, y: 2 },
+        ),
+        (
+            Shape::Rect {
+                w: 3,
+                h: 4,
+                label: "box".to_string(),
+            },
+            TaggedShape::Rect {
+                w: 3,
+                h: 4,
+                label: "box".to_string(),
+            },
+        ),
+    ] {
+        let serialized = value.serialize(&serializer).unwrap();
+        assert_eq!(
+            String::from(js_sys::JSON::stringify(&serialized).unwrap()),
+            serde_json::to_string(&tagged).unwrap()
+        );
+        assert_eq!(deserialize(serialized.clone()).unwrap(), value);
+        // The default representation is still accepted.
+        assert_eq!(deserialize(to_value(&value).unwrap()).unwrap(), value);
+        // But the tagged one requires opting in.
+        from_value::<Shape>(serialized).unwrap_err();
+    }
+
+    // Other variant kinds are unaffected.
+    for (value, json) in [
+        (Shape::Circle(2.5), r#"{"Circle":2.5}"#),
+        (Shape::Empty, r#""Empty""#),
+    ] {
+        let serialized = value.serialize(&serializer).unwrap();
+        assert_eq!(js_sys::JSON::stringify(&serialized).unwrap(), json);
+        assert_eq!(deserialize(serialized).unwrap(), value);
+    }
 }
 
 #[wasm_bindgen_test]
-fn maps_objects_object_key() {
-    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
-    struct Struct<A, B> {
-        a: A,
-        b: B,
+fn enums_adjacent_variant_tag() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Node {
+        User { id: u32, name: String },
+        Pair(i32, i32),
+        Count(u32),
+        Anonymous,
     }
 
-    let serializer = Serializer::new().serialize_maps_as_objects(true);
+    let keys = Some(("__typename", "data"));
+    let serializer = Serializer::new().adjacent_variant_tag(keys);
+    let deserialize =
+        |value: JsValue| Node::deserialize(Deserializer::from(value).adjacent_variant_tag(keys));
+    for (value, json) in [
+        (
+            Node::User {
+                id: 1,
+                name: "Alice".to_string(),
+            },
+            r#"{"__typename":"User","data":{"id":1,"name":"Alice"}}"#,
+        ),
+        (Node::Pair(1, 2), r#"{"__typename":"Pair","data":[1,2]}"#),
+        (Node::Count(3), r#"{"__typename":"Count","data":3}"#),
+        (Node::Anonymous, r#"{"__typename":"Anonymous"}"#),
+    ] {
+        let serialized = value.serialize(&serializer).unwrap();
+        assert_eq!(js_sys::JSON::stringify(&serialized).unwrap(), json);
+        assert_eq!(deserialize(serialized.clone()).unwrap(), value);
+        // The default representation is still accepted.
+        assert_eq!(deserialize(to_value(&value).unwrap()).unwrap(), value);
+        // But the tagged one requires opting in.
+        from_value::<Node>(serialized).unwrap_err();
+    }
+
+    // Both keys are configurable, and take precedence over other variant options.
+    let serializer = Serializer::new()
+        .adjacent_variant_tag(Some(("kind", "value")))
+        .unit_variants_as_index(true)
+        .struct_variant_tag(Some("type"));
+    for (value, json) in [
+        (
+            Node::User {
+                id: 1,
+                name: "Alice".to_string(),
+            },
+            r#"{"kind":"User","value":{"id":1,"name":"Alice"}}"#,
+        ),
+        (Node::Anonymous, r#"{"kind":"Anonymous"}"#),
+    ] {
+        let serialized = value.serialize(&serializer).unwrap();
+        assert_eq!(js_sys::JSON::stringify(&serialized).unwrap(), json);
+        let deserializer =
+            Deserializer::from(serialized).adjacent_variant_tag(Some(("kind", "value")));
+        assert_eq!(Node::deserialize(deserializer).unwrap(), value);
+    }
+
+    // Unknown variants and mismatched payloads are still errors.
+    let bad = js_sys::JSON::parse(r#"{"__typename":"Nope","data":1}"#).unwrap();
+    deserialize(bad).unwrap_err();
+    let bad = js_sys::JSON::parse(r#"{"__typename":"Count"}"#).unwrap();
+    deserialize(bad).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn enums_indexed_variant_tag() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Node {
+        User { id: u32, name: String },
+        Anonymous,
+        Point { x: i32, y: i32 },
+        Count(u32),
+    }
+
+    let keys = Some(("kind", "index"));
+    let serializer = Serializer::new().indexed_variant_tag(keys);
+    let parse = |json: &str| js_sys::JSON::parse(json).unwrap();
+    let deserialize =
+        |value: JsValue| Node::deserialize(Deserializer::from(value).indexed_variant_tag(keys));
+    for (value, json) in [
+        (
+            Node::User {
+                id: 1,
+                name: "Alice".to_string(),
+            },
+            r#"{"kind":"User","index":0,"id":1,"name":"Alice"}"#,
+        ),
+        (Node::Anonymous, r#"{"kind":"Anonymous","index":1}"#),
+        (
+            Node::Point { x: 1, y: -2 },
+            r#"{"kind":"Point","index":2,"x":1,"y":-2}"#,
+        ),
+        // Newtype and tuple variants aren't affected.
+        (Node::Count(3), r#"{"Count":3}"#),
+    ] {
+        let serialized = value.serialize(&serializer).unwrap();
+        assert_eq!(js_sys::JSON::stringify(&serialized).unwrap(), json);
+        assert_eq!(deserialize(serialized).unwrap(), value);
+        // The default representation is still accepted.
+        assert_eq!(deserialize(to_value(&value).unwrap()).unwrap(), value);
+    }
+    from_value::<Node>(parse(r#"{"kind":"Point","index":2,"x":1,"y":-2}"#)).unwrap_err();
+
+    // Either key is enough on its own...
+    let point = Node::Point { x: 1, y: -2 };
+    assert_eq!(
+        deserialize(parse(r#"{"index":2,"x":1,"y":-2}"#)).unwrap(),
+        point
+    );
+    assert_eq!(
+        deserialize(parse(r#"{"kind":"Point","x":1,"y":-2}"#)).unwrap(),
+        point
+    );
+    assert_eq!(
+        deserialize(parse(r#"{"index":1}"#)).unwrap(),
+        Node::Anonymous
+    );
+    assert_eq!(
+        deserialize(parse(r#"{"kind":"Anonymous"}"#)).unwrap(),
+        Node::Anonymous
+    );
+    // ...but the index takes precedence over the name.
+    assert_eq!(
+        deserialize(parse(r#"{"kind":"User","index":2,"x":1,"y":-2}"#)).unwrap(),
+        point
+    );
+
+    // Both keys are configurable, and take precedence over `struct_variant_tag`.
+    let serializer = Serializer::new()
+        .indexed_variant_tag(Some(("$type", "$index")))
+        .struct_variant_tag(Some("type"))
+        .unit_variants_as_index(true)
+        .variant_case(Case::Snake);
+    let deserializer = |value: JsValue| {
+        Deserializer::from(value)
+            .indexed_variant_tag(Some(("$type", "$index")))
+            .variant_case(Case::Snake)
+    };
+    let serialized = point.serialize(&serializer).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&serialized).unwrap(),
+        r#"{"$type":"point","$index":2,"x":1,"y":-2}"#
+    );
+    assert_eq!(Node::deserialize(deserializer(serialized)).unwrap(), point);
+    let serialized = Node::Anonymous.serialize(&serializer).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&serialized).unwrap(),
+        r#"{"$type":"anonymous","$index":1}"#
+    );
+    assert_eq!(
+        Node::deserialize(deserializer(serialized)).unwrap(),
+        Node::Anonymous
+    );
+
+    // Unknown variants and mismatched payloads are still errors.
+    deserialize(parse(r#"{"index":4}"#)).unwrap_err();
+    deserialize(parse(r#"{"kind":"Nope"}"#)).unwrap_err();
+    deserialize(parse(r#"{"index":2,"x":1}"#)).unwrap_err();
+    deserialize(parse(r#"{"index":3}"#)).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn enums_struct_variant_tag_fields() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Shape {
+        Point {
+            x: i32,
+            y: i32,
+            label: Option<String>,
+        },
+        Group {
+            name: String,
+            #[serde(flatten)]
+            rest: BTreeMap<String, i32>,
+        },
+    }
+
+    let deserialize = |json: &str| {
+        Shape::deserialize(
+            Deserializer::from(js_sys::JSON::parse(json).unwrap()).struct_variant_tag(Some("type")),
+        )
+    };
+
+    assert_eq!(
+        deserialize(r#"{"type": "Point", "x": 1, "y": 2}"#).unwrap(),
+        Shape::Point {
+            x: 1,
+            y: 2,
+            label: None
+        }
+    );
+    assert_eq!(
+        deserialize(r#"{"x": 1, "type": "Point", "y": 2, "label": "a", "extra": true}"#).unwrap(),
+        Shape::Point {
+            x: 1,
+            y: 2,
+            label: Some("a".to_string())
+        }
+    );
+
+    // The tag is not one of the fields, even for variants collecting all of the remaining ones.
+    assert_eq!(
+        deserialize(r#"{"type": "Group", "name": "g", "a": 1, "b": 2}"#).unwrap(),
+        Shape::Group {
+            name: "g".to_string(),
+            rest: btreemap! {
+                "a".to_string() => 1,
+                "b".to_string() => 2,
+            },
+        }
+    );
+
+    deserialize(r#"{"type": "Point", "x": 1}"#).unwrap_err();
+    deserialize(r#"{"type": "Line", "x": 1, "y": 2}"#).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn enums_variant_keys_as_index() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Line(f64, f64),
+        Rect {
+            w: f64,
+            h: f64,
+        },
+        #[serde(rename = "9")]
+        Renamed(bool),
+    }
+
+    let serializer = Serializer::new().variant_keys_as_index(true);
+    for (value, json) in [
+        (Shape::Empty, r#""Empty""#),
+        (Shape::Circle(2.5), r#"{"1":2.5}"#),
+        (Shape::Line(1.0, 2.0), r#"{"2":[1,2]}"#),
+        (Shape::Rect { w: 1.0, h: 2.0 }, r#"{"3":{"w":1,"h":2}}"#),
+        (Shape::Renamed(true), r#"{"4":true}"#),
+    ] {
+        let serialized = value.serialize(&serializer).unwrap();
+        assert_eq!(js_sys::JSON::stringify(&serialized).unwrap(), json);
+        assert_eq!(from_value::<Shape>(serialized).unwrap(), value);
+    }
+
+    // Both options can be combined for fully index-based output.
+    let serializer = serializer.unit_variants_as_index(true);
+    test_via_into_with_config(Shape::Empty, 0, &serializer);
+
+    // Keys matching a variant name take priority, and only canonical indices are accepted.
+    assert_eq!(
+        from_value::<Shape>(js_sys::JSON::parse(r#"{"9":false}"#).unwrap()).unwrap(),
+        Shape::Renamed(false)
+    );
+    from_value::<Shape>(js_sys::JSON::parse(r#"{"01":2.5}"#).unwrap()).unwrap_err();
+    from_value::<Shape>(js_sys::JSON::parse(r#"{"5":2.5}"#).unwrap()).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn enums_variant_wrappers_as_maps() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Label {
+        #[serde(rename = "has space")]
+        Spaced(String),
+        #[serde(rename = "größe")]
+        Size(u32, u32),
+        #[serde(rename = "🎨 style")]
+        Style { color: String },
+        #[serde(rename = "no data")]
+        Empty,
+    }
+
+    let serializer = Serializer::new().variant_wrappers_as_maps(true);
+    for (value, key, payload) in [
+        (Label::Spaced("a b".to_string()), "has space", r#""a b""#),
+        (Label::Size(1, 2), "größe", "[1,2]"),
+        (
+            Label::Style {
+                color: "red".to_string(),
+            },
+            "🎨 style",
+            r#"{"color":"red"}"#,
+        ),
+    ] {
+        let serialized = value.serialize(&serializer).unwrap();
+        let map = serialized.dyn_ref::<js_sys::Map>().unwrap();
+        assert_eq!(map.size(), 1);
+        assert_eq!(
+            js_sys::JSON::stringify(&map.get(&key.into())).unwrap(),
+            payload
+        );
+        // Both the `Map` and the default object forms are accepted.
+        assert_eq!(from_value::<Label>(serialized).unwrap(), value);
+        assert_eq!(
+            from_value::<Label>(to_value(&value).unwrap()).unwrap(),
+            value
+        );
+    }
+
+    // Unit variants are still strings.
+    test_via_into_with_config(Label::Empty, "no data", &serializer);
+
+    // Same rules apply as for objects.
+    let map = js_sys::Map::new()
+        .set(&"has space".into(), &"a".into())
+        .set(&"größe".into(), &JsValue::from(1));
+    from_value::<Label>(map.into()).unwrap_err();
+    let map = js_sys::Map::new().set(&JsValue::from(1), &"a".into());
+    from_value::<Label>(map.into()).unwrap_err();
+    let map = js_sys::Map::new()
+        .set(&"$meta".into(), &JsValue::NULL)
+        .set(&"has space".into(), &"a".into());
+    let deserializer = Deserializer::from(JsValue::from(map)).ignore_variant_key_prefixes(&["$"]);
+    assert_eq!(
+        Label::deserialize(deserializer).unwrap(),
+        Label::Spaced("a".to_string())
+    );
+}
+
+#[wasm_bindgen_test]
+fn enums_with_metadata_keys() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Shape {
+        Circle { radius: f64 },
+        Square(f64),
+    }
+
+    let meta = js_sys::JSON::parse(r#"{"$meta": {"id": 1}, "Circle": {"radius": 2}}"#).unwrap();
+    let _ = from_value::<Shape>(meta.clone()).unwrap_err();
+
+    let deserializer = Deserializer::from(meta).ignore_variant_key_prefixes(&["$", "_"]);
+    assert_eq!(
+        Shape::deserialize(deserializer).unwrap(),
+        Shape::Circle { radius: 2.0 }
+    );
+
+    let meta = js_sys::JSON::parse(r#"{"_rev": 3, "Square": 4, "$meta": null}"#).unwrap();
+    let deserializer = Deserializer::from(meta).ignore_variant_key_prefixes(&["$", "_"]);
+    assert_eq!(
+        Shape::deserialize(deserializer).unwrap(),
+        Shape::Square(4.0)
+    );
+
+    // Only metadata keys means there's no variant key at all.
+    let meta = js_sys::JSON::parse(r#"{"$meta": {}}"#).unwrap();
+    let deserializer = Deserializer::from(meta).ignore_variant_key_prefixes(&["$"]);
+    Shape::deserialize(deserializer).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn results_as_js_result() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Response {
+        #[serde(with = "serde_wasm_bindgen::as_js_result")]
+        result: Result<i32, String>,
+        #[serde(with = "serde_wasm_bindgen::as_js_result")]
+        done: Result<(), String>,
+    }
+
+    let ok = Response {
+        result: Ok(42),
+        done: Ok(()),
+    };
+    let value = ok.serialize(&Serializer::json_compatible()).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"result":{"ok":true,"value":42},"done":{"ok":true,"value":null}}"#
+    );
+    assert_eq!(from_value::<Response>(value).unwrap(), ok);
+
+    let err = Response {
+        result: Err("failed".to_string()),
+        done: Err("also failed".to_string()),
+    };
+    let value = to_value(&err).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"result":{"ok":false,"error":"failed"},"done":{"ok":false,"error":"also failed"}}"#
+    );
+    assert_eq!(from_value::<Response>(value).unwrap(), err);
+
+    // Mismatched payloads are an error.
+    let value = js_sys::JSON::parse(
+        r#"{"result":{"ok":true,"error":"oops"},"done":{"ok":true,"value":null}}"#,
+    )
+    .unwrap();
+    from_value::<Response>(value).unwrap_err();
+
+    // `Result` fields without the attribute keep the serde-json compatible form.
+    test_via_json::<Result<i32, String>>(Ok(1));
+    test_via_json::<Result<i32, String>>(Err("failed".to_string()));
+}
+
+#[wasm_bindgen_test]
+fn enums_with_discriminator() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Event {
+        Click { x: i32, y: i32 },
+        Key { code: String },
+    }
+
+    fn discriminate(value: &JsValue) -> Option<&'static str> {
+        if !value.is_object() {
+            None
+        } else if Reflect::has(value, &"code".into()).unwrap() {
+            Some("Key")
+        } else if Reflect::has(value, &"x".into()).unwrap() {
+            Some("Click")
+        } else {
+            None
+        }
+    }
+
+    let events =
+        js_sys::JSON::parse(r#"[{"x": 1, "y": 2}, {"code": "Enter"}, {"Key": {"code": "Tab"}}]"#)
+            .unwrap();
+
+    // Without a discriminator, only the last one is in the default representation.
+    from_value::<Vec<Event>>(events.clone()).unwrap_err();
+
+    let deserializer = Deserializer::from(events).enum_discriminator(discriminate);
+    assert_eq!(
+        Vec::<Event>::deserialize(deserializer).unwrap(),
+        [
+            Event::Click { x: 1, y: 2 },
+            Event::Key {
+                code: "Enter".to_string()
+            },
+            // Falls back to the default representation when returning `None`.
+            Event::Key {
+                code: "Tab".to_string()
+            },
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+fn structs() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Unit;
+
+    test_via_into(Unit, JsValue::UNDEFINED);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Newtype<A>(A);
+
+    test_via_json(Newtype("newtype content".to_string()));
+
+    // Single-field tuple structs are newtypes in serde's data model,
+    // so they are transparent rather than 1-element arrays.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct One(i32);
+
+    test_via_into(One(42), 42);
+    assert!(!Array::is_array(&to_value(&One(42)).unwrap()));
+
+    // An explicit 1-tuple inside is still a sequence.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct OneTuple((i32,));
+
+    test_via_json(OneTuple((42,)));
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Tuple<A, B>(A, B);
+
+    test_via_json(Tuple("tuple content".to_string(), 42));
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Struct<A, B> {
+        a: A,
+        b: B,
+    }
+
+    test_via_json(Struct {
+        a: "struct content".to_string(),
+        b: 42,
+    });
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct OptionalFields {
+        name: String,
+        count: u32,
+        missing: Option<bool>,
+    }
+
+    // Structs can be deserialized from ES2015 Maps with string keys too.
+    let map = js_sys::Map::new();
+    map.set(&"count".into(), &3.into());
+    map.set(&"name".into(), &"from map".into());
+    map.set(&"extra".into(), &JsValue::TRUE);
+    assert_eq!(
+        from_value::<OptionalFields>(map.into()).unwrap(),
+        OptionalFields {
+            name: "from map".to_string(),
+            count: 3,
+            missing: None,
+        }
+    );
+
+    let map = js_sys::Map::new();
+    map.set(&"name".into(), &"from map".into());
+    from_value::<OptionalFields>(map.into()).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn structs_skip_none_fields() {
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Sparse {
+        name: Option<String>,
+        count: Option<u32>,
+        enabled: Option<bool>,
+        ratio: Option<f64>,
+        tags: Option<Vec<String>>,
+        unit: Option<()>,
+    }
+
+    let sparse = Sparse {
+        count: Some(0),
+        tags: Some(vec![]),
+        ..Default::default()
+    };
+    let keys = |value: &JsValue| Object::keys(value.unchecked_ref::<Object>()).join(",");
+
+    // By default, `None` fields are still set, just to `undefined`.
+    let value = to_value(&sparse).unwrap();
+    assert_eq!(keys(&value), "name,count,enabled,ratio,tags,unit");
+
+    let serializer = Serializer::new().skip_none_fields(true);
+    let value = sparse.serialize(&serializer).unwrap();
+    assert_eq!(keys(&value), "count,tags");
+    assert_eq!(from_value::<Sparse>(value).unwrap(), sparse);
+
+    let value = Sparse::default().serialize(&serializer).unwrap();
+    assert_eq!(keys(&value), "");
+    assert_eq!(from_value::<Sparse>(value).unwrap(), Sparse::default());
+
+    // Only `undefined` is skipped, not `null`.
+    let serializer = Serializer::new()
+        .skip_none_fields(true)
+        .serialize_missing_as_null(true);
+    let value = sparse.serialize(&serializer).unwrap();
+    assert_eq!(keys(&value), "name,count,enabled,ratio,tags,unit");
+}
+
+#[wasm_bindgen_test]
+fn structs_field_order() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Inner {
+        second: u8,
+        first: u8,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Unordered {
+        zulu: u8,
+        alpha: u8,
+        #[serde(rename = "Mike")]
+        mike: u8,
+        bravo: Inner,
+        #[serde(rename = "_yankee")]
+        yankee: u8,
+        charlie: Option<u8>,
+    }
+
+    let value = Unordered {
+        zulu: 1,
+        alpha: 2,
+        mike: 3,
+        bravo: Inner {
+            second: 4,
+            first: 5,
+        },
+        yankee: 6,
+        charlie: None,
+    };
+    let keys = |value: &JsValue| Object::keys(value.unchecked_ref::<Object>()).join(",");
+
+    // Keys follow the declaration order rather than being sorted, including in nested structs.
+    let serialized = to_value(&value).unwrap();
+    assert_eq!(keys(&serialized), "zulu,alpha,Mike,bravo,_yankee,charlie");
+    let bravo = Reflect::get(&serialized, &"bravo".into()).unwrap();
+    assert_eq!(keys(&bravo), "second,first");
+    assert_eq!(
+        js_sys::JSON::stringify(&serialized).unwrap(),
+        r#"{"zulu":1,"alpha":2,"Mike":3,"bravo":{"second":4,"first":5},"_yankee":6}"#
+    );
+    assert_eq!(from_value::<Unordered>(serialized).unwrap(), value);
+
+    // Options reordering maps don't affect structs.
+    let serializer = Serializer::new()
+        .sort_map_keys(true)
+        .serialize_missing_as_null(true);
+    let serialized = value.serialize(&serializer).unwrap();
+    assert_eq!(keys(&serialized), "zulu,alpha,Mike,bravo,_yankee,charlie");
+}
+
+#[wasm_bindgen_test]
+fn structs_columnar() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Row {
+        id: u32,
+        name: String,
+        score: Option<f64>,
+    }
+
+    let rows = vec![
+        Row {
+            id: 1,
+            name: "a".to_string(),
+            score: Some(0.5),
+        },
+        Row {
+            id: 2,
+            name: "b".to_string(),
+            score: None,
+        },
+        Row {
+            id: 3,
+            name: "c".to_string(),
+            score: Some(1.5),
+        },
+    ];
+
+    let value = to_columnar(&rows).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"id":[1,2,3],"name":["a","b","c"],"score":[0.5,null,1.5]}"#
+    );
+    assert!(Array::is_array(
+        &Reflect::get(&value, &"id".into()).unwrap()
+    ));
+    assert!(Reflect::get(&value, &"score".into())
+        .unwrap()
+        .unchecked_into::<Array>()
+        .get(1)
+        .is_undefined());
+
+    // Columns transposed back into rows deserialize into the original structs.
+    let transpose = js_sys::Function::new_with_args(
+        "columns",
+        "const keys = Object.keys(columns);
+        return columns[keys[0]].map((_, i) => Object.fromEntries(keys.map(k => [k, columns[k][i]])));",
+    );
+    let value = transpose.call1(&JsValue::UNDEFINED, &value).unwrap();
+    assert_eq!(from_value::<Vec<Row>>(value).unwrap(), rows);
+
+    // Fields are serialized with the serializer options.
+    let value = Serializer::json_compatible()
+        .serialize_columnar(&rows[1..])
+        .unwrap();
+    assert!(Reflect::get(&value, &"score".into())
+        .unwrap()
+        .unchecked_into::<Array>()
+        .get(0)
+        .is_null());
+
+    // Skipped fields keep the columns aligned.
+    #[derive(Serialize)]
+    struct Sparse {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        a: Option<u32>,
+        b: u32,
+    }
+
+    let value = to_columnar(&[
+        Sparse { a: None, b: 1 },
+        Sparse { a: Some(2), b: 2 },
+        Sparse { a: None, b: 3 },
+    ])
+    .unwrap();
+    let column = |key: &str| {
+        Reflect::get(&value, &key.into())
+            .unwrap()
+            .unchecked_into::<Array>()
+    };
+    assert_eq!(column("a").length(), 3);
+    assert!(column("a").get(0).is_undefined());
+    assert_eq!(column("a").get(1), 2);
+    assert!(column("a").get(2).is_undefined());
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"b":[1,2,3],"a":[null,2,null]}"#
+    );
+
+    assert_eq!(
+        js_sys::JSON::stringify(&to_columnar::<Row>(&[]).unwrap()).unwrap(),
+        "{}"
+    );
+    to_columnar(&[1, 2, 3]).unwrap_err();
+    to_columnar(&[(1, 2)]).unwrap_err();
+    to_columnar(&[btreemap! { "a" => 1 }]).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn structs_from_columnar() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Row {
+        id: u32,
+        name: String,
+        score: Option<f64>,
+    }
+
+    let parse = |json: &str| js_sys::JSON::parse(json).unwrap();
+
+    let rows = from_columnar::<Row>(parse(
+        r#"{"id":[1,2],"name":["a","b"],"score":[0.5,null],"extra":[true,false]}"#,
+    ))
+    .unwrap();
+    assert_eq!(
+        rows,
+        [
+            Row {
+                id: 1,
+                name: "a".to_string(),
+                score: Some(0.5),
+            },
+            Row {
+                id: 2,
+                name: "b".to_string(),
+                score: None,
+            },
+        ]
+    );
+
+    // Round-trips with `to_columnar`, including through other row types.
+    let value = to_columnar(&rows).unwrap();
+    assert_eq!(from_columnar::<Row>(value.clone()).unwrap(), rows);
+    let maps = from_columnar::<BTreeMap<String, serde_json::Value>>(value).unwrap();
+    assert_eq!(maps[1]["name"], "b");
+    assert!(maps[1]["score"].is_null());
+
+    // Missing columns are missing fields.
+    let rows = from_columnar::<Row>(parse(r#"{"id":[1],"name":["a"]}"#)).unwrap();
+    assert_eq!(rows[0].score, None);
+    from_columnar::<Row>(parse(r#"{"id":[1],"score":[1]}"#)).unwrap_err();
+
+    assert_eq!(from_columnar::<Row>(parse("{}")).unwrap(), []);
+    assert_eq!(
+        from_columnar::<Row>(parse(r#"{"id":[],"name":[]}"#)).unwrap(),
+        []
+    );
+
+    // Ragged columns are rejected.
+    let error = from_columnar::<Row>(parse(r#"{"id":[1,2,3],"name":["a","b"]}"#)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "SerdeWasmBindgenError: column `name` has 2 values, but column `id` has 3"
+    );
+    let error = from_columnar::<Row>(parse(r#"{"id":[1],"name":"a"}"#)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "SerdeWasmBindgenError: column `name` is not an array"
+    );
+    from_columnar::<Row>(parse("[[1],[2]]")).unwrap_err();
+    from_columnar::<Row>(JsValue::from(1)).unwrap_err();
+
+    // Errors in cells are reported as usual.
+    from_columnar::<Row>(parse(r#"{"id":[1,-2],"name":["a","b"]}"#)).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn annotated_types() {
+    #[derive(Serialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Serialize)]
+    enum Shape {
+        Circle { center: Point, radius: u32 },
+    }
+
+    let rust_type = JsValue::from("$rustType");
+
+    let value = to_value(&Point { x: 1, y: 2 }).unwrap();
+    assert!(!Reflect::has(&value, &rust_type).unwrap());
+
+    let serializer = Serializer::new().annotate_types(true);
+    let value = Point { x: 1, y: 2 }.serialize(&serializer).unwrap();
+    assert_eq!(Reflect::get(&value, &rust_type).unwrap(), "Point");
+    assert!(!value
+        .unchecked_ref::<Object>()
+        .property_is_enumerable(&rust_type));
+    assert_eq!(Object::keys(value.unchecked_ref::<Object>()).length(), 2);
+    assert_eq!(js_sys::JSON::stringify(&value).unwrap(), r#"{"x":1,"y":2}"#);
+
+    let value = Shape::Circle {
+        center: Point { x: 0, y: 0 },
+        radius: 1,
+    }
+    .serialize(&serializer)
+    .unwrap();
+    let payload = Reflect::get(&value, &"Circle".into()).unwrap();
+    assert_eq!(Reflect::get(&payload, &rust_type).unwrap(), "Shape");
+    let center = Reflect::get(&payload, &"center".into()).unwrap();
+    assert_eq!(Reflect::get(&center, &rust_type).unwrap(), "Point");
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"Circle":{"center":{"x":0,"y":0},"radius":1}}"#
+    );
+}
+
+#[wasm_bindgen_test]
+fn sequences() {
+    test_via_json([1, 2]);
+    test_via_json(["".to_string(), "x".to_string(), "xyz".to_string()]);
+    test_via_json((100, "xyz".to_string(), true));
+
+    // Sets are currently indistinguishable from other sequences for
+    // Serde serialisers, so this will become an array on the JS side.
+    test_via_json(hashset! {false, true});
+}
+
+#[wasm_bindgen_test]
+fn sequences_with_inaccurate_len_hint() {
+    use serde::ser::SerializeSeq;
+
+    struct Hinted(Option<usize>, &'static [u8]);
+
+    impl Serialize for Hinted {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(self.0)?;
+            for item in self.1 {
+                seq.serialize_element(item)?;
+            }
+            seq.end()
+        }
+    }
+
+    for hint in [None, Some(0), Some(2), Some(3), Some(10)] {
+        let value = to_value(&Hinted(hint, &[1, 2, 3])).unwrap();
+        assert_eq!(
+            js_sys::JSON::stringify(&value).unwrap(),
+            "[1,2,3]",
+            "{:?}",
+            hint
+        );
+        assert_eq!(value.unchecked_ref::<Array>().length(), 3);
+    }
+}
+
+#[wasm_bindgen_test]
+fn sequences_streaming() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use wasm_bindgen::closure::Closure;
+
+    fn collect(serializer: &Serializer, value: &impl Serialize) -> Result<Array, Error> {
+        let items = Array::new();
+        let callback = Closure::<dyn FnMut(JsValue)>::new({
+            let items = items.clone();
+            move |item: JsValue| {
+                items.push(&item);
+            }
+        });
+        serializer.serialize_seq_streaming(value, callback.as_ref().unchecked_ref())?;
+        Ok(items)
+    }
+
+    let serializer = Serializer::new();
+    let items = collect(&serializer, &vec![1, 2, 3]).unwrap();
+    assert_json(items.into(), vec![1, 2, 3]);
+
+    // Fixed-size arrays, tuples and tuple structs are sequences too.
+    #[derive(Serialize)]
+    struct Pair(u8, &'static str);
+
+    let items = collect(&serializer, &[4, 5, 6]).unwrap();
+    assert_json(items.into(), vec![4, 5, 6]);
+    let items = collect(&serializer, &(1, "a")).unwrap();
+    assert_eq!(js_sys::JSON::stringify(&items).unwrap(), r#"[1,"a"]"#);
+    let items = collect(&serializer, &Pair(2, "b")).unwrap();
+    assert_eq!(js_sys::JSON::stringify(&items).unwrap(), r#"[2,"b"]"#);
+
+    #[derive(Serialize)]
+    struct Rows(Vec<BTreeMap<&'static str, i32>>);
+
+    // Elements are serialized with the given configuration, and newtype wrappers are transparent.
+    let rows = Rows(vec![btreemap! { "a" => 1 }, btreemap! { "b" => 2 }]);
+    let serializer = Serializer::new().serialize_maps_as_objects(true);
+    let items = collect(&serializer, &rows).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&items).unwrap(),
+        r#"[{"a":1},{"b":2}]"#
+    );
+
+    // Each element is passed on as soon as it's serialized, and errors stop the stream.
+    struct Counted(Rc<Cell<u32>>);
+
+    impl Serialize for Counted {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.set(self.0.get() + 1);
+            serializer.serialize_u32(self.0.get())
+        }
+    }
+
+    let count = Rc::new(Cell::new(0));
+    let values: Vec<_> = (0..5).map(|_| Counted(count.clone())).collect();
+    let callback = js_sys::Function::new_with_args(
+        "item",
+        "if (item === 2) throw new RangeError('stream closed')",
+    );
+    let error = serde_wasm_bindgen::serialize_seq_streaming(&values, &callback).unwrap_err();
+    assert_eq!(count.get(), 2);
+    let error = JsValue::from(error).unchecked_into::<js_sys::Error>();
+    assert!(error.cause().is_instance_of::<js_sys::RangeError>());
+
+    // Anything but sequences is rejected.
+    let callback = js_sys::Function::new_no_args("throw new Error('unexpected call')");
+    for error in [
+        serde_wasm_bindgen::serialize_seq_streaming(&1, &callback).unwrap_err(),
+        serde_wasm_bindgen::serialize_seq_streaming(&btreemap! { 1 => 2 }, &callback).unwrap_err(),
+        serde_wasm_bindgen::serialize_seq_streaming(&Some(vec![1]), &callback).unwrap_err(),
+    ] {
+        assert!(error
+            .to_string()
+            .contains("only sequences can be serialized as a stream"));
+    }
+}
+
+#[wasm_bindgen_test]
+fn maps() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+    struct Struct<A, B> {
+        a: A,
+        b: B,
+    }
+
+    // Create a Rust HashMap with non-string keys to make sure
+    // that we support real arbitrary maps.
+    let src = hashmap! {
+        Struct {
+            a: 1,
+            b: "smth".to_string(),
+        } => Struct {
+            a: 2,
+            b: "SMTH".to_string(),
+        },
+        Struct {
+            a: 42,
+            b: "something".to_string(),
+        } => Struct {
+            a: 84,
+            b: "SOMETHING".to_string(),
+        },
+    };
+
+    // Convert to a JS value
+    let res = to_value(&src).unwrap();
+
+    // Make sure that the result is an ES6 Map.
+    let res = res.dyn_into::<js_sys::Map>().unwrap();
+    assert_eq!(res.size() as usize, src.len());
+
+    // Compare values one by one (it's ok to use JSON for invidivual structs).
+    res.entries()
+        .into_iter()
+        .map(|kv| kv.unwrap())
+        .zip(src)
+        .for_each(|(lhs_kv, rhs_kv)| {
+            assert_json(lhs_kv, rhs_kv);
+        });
+}
+
+#[wasm_bindgen_test]
+fn maps_from_entries() {
+    let parse = |json: &str| from_value::<HashMap<String, i32>>(js_sys::JSON::parse(json).unwrap());
+
+    // Same input as `new Map(entries)` accepts.
+    let expected = hashmap! { "a".to_string() => 1, "b".to_string() => 2 };
+    assert_eq!(parse(r#"[["a", 1], ["b", 2]]"#).unwrap(), expected);
+    assert_eq!(parse("[]").unwrap(), HashMap::new());
+    let entries = Array::of2(
+        &Array::of2(&"a".into(), &1.into()),
+        &Array::of2(&"b".into(), &2.into()),
+    );
+    assert_eq!(
+        from_value::<BTreeMap<String, i32>>(entries.into()).unwrap(),
+        btreemap! { "a".to_string() => 1, "b".to_string() => 2 }
+    );
+
+    // Each entry must be exactly a `[key, value]` pair.
+    let error = |json: &str| parse(json).unwrap_err().to_string();
+    assert!(
+        error(r#"[["a", 1], ["b"]]"#).contains("invalid length 1, expected a [key, value] pair")
+    );
+    assert!(error(r#"[["a", 1, 2]]"#).contains("invalid length 3, expected a [key, value] pair"));
+    assert!(error(r#"[{"0": "a", "1": 1}]"#).contains("expected a [key, value] pair"));
+    assert!(
+        error(r#"["ab"]"#).contains(r#"invalid type: string "ab", expected a [key, value] pair"#)
+    );
+    assert!(error(r#"[["a", "1"]]"#).contains("invalid type: string"));
+}
+
+#[wasm_bindgen_test]
+fn maps_objects_string_key() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+    struct Struct<A, B> {
+        a: A,
+        b: B,
+    }
+
+    let src = hashmap! {
+        "a".to_string() => Struct {
+            a: 2,
+            b: "S".to_string(),
+        },
+        "b".to_string() => Struct {
+            a: 3,
+            b: "T".to_string(),
+        },
+    };
+
+    test_via_json_with_config(src, Serializer::new().serialize_maps_as_objects(true));
+}
+
+#[wasm_bindgen_test]
+fn maps_inherited_properties() {
+    let set = |obj: &Object, key: &str, value: u32| {
+        Reflect::set(obj, &key.into(), &value.into()).unwrap();
+    };
+    let base = Object::new();
+    set(&base, "base", 1);
+    let proto = Object::create(&base);
+    set(&proto, "inherited", 2);
+    set(&proto, "shadowed", 3);
+    set(&proto, "hidden", 4);
+    let non_enumerable = Object::new();
+    Reflect::set(&non_enumerable, &"value".into(), &5.into()).unwrap();
+    Object::define_property(&proto, &"notEnumerable".into(), &non_enumerable);
+    let obj = Object::create(&proto);
+    set(&obj, "own", 6);
+    set(&obj, "shadowed", 7);
+    // A non-enumerable own property still shadows an enumerable inherited one.
+    Object::define_property(&obj, &"hidden".into(), &non_enumerable);
+
+    let deserialize = |include_inherited_properties| {
+        BTreeMap::<String, u32>::deserialize(
+            Deserializer::from(JsValue::from(&obj))
+                .include_inherited_properties(include_inherited_properties),
+        )
+        .unwrap()
+    };
+
+    // Only own properties are read by default.
+    assert_eq!(
+        deserialize(false),
+        btreemap! {
+            "own".to_string() => 6,
+            "shadowed".to_string() => 7,
+        }
+    );
+
+    // Inherited ones are added from the whole prototype chain, unless shadowed.
+    assert_eq!(
+        deserialize(true),
+        btreemap! {
+            "own".to_string() => 6,
+            "shadowed".to_string() => 7,
+            "inherited".to_string() => 2,
+            "base".to_string() => 1,
+        }
+    );
+
+    // Self-describing values see them too.
+    let value = serde_json::Value::deserialize(
+        Deserializer::from(JsValue::from(&obj)).include_inherited_properties(true),
+    )
+    .unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({"own": 6, "shadowed": 7, "inherited": 2, "base": 1})
+    );
+}
+
+#[wasm_bindgen_test]
+fn serializer_config_is_cloneable() {
+    let template = Serializer::json_compatible().serialize_large_number_types_as_bigints(true);
+    let serializer = template.clone();
+
+    assert_eq!(format!("{:?}", serializer), format!("{:?}", template));
+    assert!(format!("{:?}", serializer).contains("serialize_maps_as_objects: true"));
+    assert!(0_u64.serialize(&serializer).unwrap().is_bigint());
+}
+
+#[wasm_bindgen_test]
+fn maps_sorted_keys() {
+    let serializer = Serializer::new().sort_map_keys(true);
+    let src = hashmap! {
+        10 => "ten",
+        1 => "one",
+        2 => "two",
+    };
+
+    let res = src.serialize(&serializer).unwrap();
+    let keys = res.dyn_into::<js_sys::Map>().unwrap().keys();
+    let keys = keys.into_iter().map(|key| key.unwrap()).collect::<Vec<_>>();
+    assert_eq!(keys, [1, 2, 10]);
+
+    let res = src
+        .serialize(&serializer.clone().serialize_maps_as_objects(true))
+        .unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&res).unwrap(),
+        r#"{"1":"one","2":"two","10":"ten"}"#
+    );
+
+    // Integer-like string keys are sorted numerically too, ahead of other strings.
+    let src = btreemap! {
+        "10".to_string() => 0,
+        "2".to_string() => 0,
+        "1".to_string() => 0,
+        "b".to_string() => 0,
+        "a".to_string() => 0,
+        "02".to_string() => 0,
+    };
+    let res = src.serialize(&serializer).unwrap();
+    let keys = res.dyn_into::<js_sys::Map>().unwrap().keys();
+    let keys = keys.into_iter().map(|key| key.unwrap()).collect::<Vec<_>>();
+    assert_eq!(keys, ["1", "2", "10", "02", "a", "b"]);
+
+    // Only valid array indices count as integers, same as for the engine's own key order, and
+    // other strings are compared by UTF-16 code units.
+    let src = btreemap! {
+        "4294967294".to_string() => 0,
+        "4294967295".to_string() => 0,
+        "4294967296".to_string() => 0,
+        "01".to_string() => 0,
+        "\u{ff61}".to_string() => 0,
+        "\u{1f600}".to_string() => 0,
+    };
+    let expected = [
+        "4294967294",
+        "01",
+        "4294967295",
+        "4294967296",
+        "\u{1f600}",
+        "\u{ff61}",
+    ];
+    let res = src.serialize(&serializer).unwrap();
+    let keys = res.dyn_into::<js_sys::Map>().unwrap().keys();
+    let keys = keys.into_iter().map(|key| key.unwrap()).collect::<Vec<_>>();
+    assert_eq!(keys, expected);
+    let res = src
+        .serialize(&serializer.clone().serialize_maps_as_objects(true))
+        .unwrap();
+    let keys = Object::keys(res.unchecked_ref::<Object>()).to_vec();
+    assert_eq!(keys, expected);
+
+    // Any float keys can be sorted, with `NaN` going last.
+    struct Entries<K, V>(Vec<(K, V)>);
+
+    impl<K: Serialize, V: Serialize> Serialize for Entries<K, V> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+        }
+    }
+
+    let src = Entries(vec![
+        (3.0, 0),
+        (f64::NAN, 0),
+        (-1.0, 0),
+        (f64::INFINITY, 0),
+        (f64::NEG_INFINITY, 0),
+    ]);
+    let res = src.serialize(&serializer).unwrap();
+    let keys = res.dyn_into::<js_sys::Map>().unwrap().keys();
+    let keys = keys
+        .into_iter()
+        .map(|key| key.unwrap().as_f64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(format!("{:?}", keys), "[-inf, -1.0, 3.0, inf, NaN]");
+
+    // Non-primitive keys can't be sorted.
+    let src = btreemap! { (1, 2) => 3 };
+    src.serialize(&serializer).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn maps_sorted_values() {
+    let entries = |value: JsValue| {
+        value
+            .dyn_into::<js_sys::Map>()
+            .unwrap()
+            .entries()
+            .into_iter()
+            .map(|entry| {
+                let entry = Array::from(&entry.unwrap());
+                (
+                    entry.get(0).as_string().unwrap(),
+                    entry.get(1).as_f64().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let frequencies = btreemap! {
+        "the" => 12,
+        "a" => 7,
+        "of" => 7,
+        "wasm" => 1,
+        "serde" => 30,
+    };
+    let serializer = Serializer::new().sort_map_values(true);
+    let res = frequencies.serialize(&serializer).unwrap();
+    // Entries with equal values stay in the original order.
+    assert_eq!(
+        entries(res),
+        [
+            ("wasm".to_string(), 1.0),
+            ("a".to_string(), 7.0),
+            ("of".to_string(), 7.0),
+            ("the".to_string(), 12.0),
+            ("serde".to_string(), 30.0),
+        ]
+    );
+
+    // Or by key, if requested.
+    let src = vec![("b", 1), ("a", 1), ("c", 0)]
+        .into_iter()
+        .collect::<IndexMap<_, _>>();
+    let res = src
+        .serialize(&serializer.clone().sort_map_keys(true))
+        .unwrap();
+    assert_eq!(
+        entries(res),
+        [
+            ("c".to_string(), 0.0),
+            ("a".to_string(), 1.0),
+            ("b".to_string(), 1.0),
+        ]
+    );
+
+    // Works for objects and string values too.
+    let src = btreemap! { "x" => "b", "y" => "a" };
+    let res = src
+        .serialize(&serializer.clone().serialize_maps_as_objects(true))
+        .unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&res).unwrap(),
+        r#"{"y":"a","x":"b"}"#
+    );
+
+    // Any float values can be sorted, with `NaN` going last, even in maps large enough
+    // for the sort to check that the order is total.
+    let src = (0..50)
+        .map(|i| {
+            (
+                i,
+                if i % 3 == 0 {
+                    f64::NAN
+                } else {
+                    f64::from(50 - i)
+                },
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+    let res = src.serialize(&serializer).unwrap();
+    let values = res.dyn_into::<js_sys::Map>().unwrap().values();
+    let values = values
+        .into_iter()
+        .map(|value| value.unwrap().as_f64().unwrap())
+        .collect::<Vec<_>>();
+    let nan_count = src.values().filter(|v| v.is_nan()).count();
+    let (numbers, nans) = values.split_at(values.len() - nan_count);
+    assert!(numbers.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(nans.iter().all(|v| v.is_nan()));
+
+    // Non-primitive values can't be sorted.
+    let src = btreemap! { "a" => (1, 2) };
+    src.serialize(&serializer).unwrap_err();
+    let src = btreemap! { "a" => None::<i32> };
+    src.serialize(&serializer).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn maps_stringified_keys() {
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    let src = btreemap! {
+        Point { x: 1, y: 2 } => "a",
+        Point { x: 3, y: 4 } => "b",
+    };
+    let serializer = Serializer::new().stringify_map_keys(true);
+
+    // Struct keys are equal by their JSON form, so they can be looked up.
+    let value = src.serialize(&serializer).unwrap();
+    let map = value.dyn_ref::<js_sys::Map>().unwrap();
+    assert_eq!(map.get(&r#"{"x":1,"y":2}"#.into()), "a");
+    assert_eq!(map.get(&r#"{"x":3,"y":4}"#.into()), "b");
+
+    // By default each key is a distinct object.
+    let value = to_value(&src).unwrap();
+    let map = value.dyn_ref::<js_sys::Map>().unwrap();
+    assert!(map.keys().next().unwrap().value().is_object());
+    assert!(map.get(&r#"{"x":1,"y":2}"#.into()).is_undefined());
+
+    // Which allows using them in plain objects too, while primitive keys are unaffected.
+    let serializer = serializer.serialize_maps_as_objects(true);
+    let value = src.serialize(&serializer).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"{\"x\":1,\"y\":2}":"a","{\"x\":3,\"y\":4}":"b"}"#
+    );
+    let value = btreemap! { vec![1, 2] => 1, vec![] => 2 }
+        .serialize(&serializer)
+        .unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"[]":2,"[1,2]":1}"#
+    );
+    let value = btreemap! { 1 => "a", 2 => "b" }
+        .serialize(&serializer)
+        .unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"1":"a","2":"b"}"#
+    );
+
+    // Keys that can't be stringified are reported.
+    let serializer = serializer.serialize_large_number_types_as_bigints(true);
+    let error = btreemap! { vec![1_u64] => 1 }
+        .serialize(&serializer)
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("Map key cannot be converted to JSON"));
+}
+
+#[wasm_bindgen_test]
+fn maps_cached_keys() {
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+    struct Key(String);
+
+    let rows: Vec<BTreeMap<String, u32>> = (0..3)
+        .map(|i| btreemap! { "id".to_string() => i, "score".to_string() => i * 10 })
+        .collect();
+
+    // The output is the same, whether the keys come from the cache or not.
+    for &as_objects in &[false, true] {
+        let serializer = Serializer::new()
+            .serialize_maps_as_objects(as_objects)
+            .cache_map_keys(true);
+        for _ in 0..2 {
+            let value = rows.serialize(&serializer).unwrap();
+            assert_eq!(
+                from_value::<Vec<BTreeMap<String, u32>>>(value).unwrap(),
+                rows
+            );
+        }
+    }
+
+    let serializer = Serializer::json_compatible().cache_map_keys(true);
+    let value = rows.serialize(&serializer).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"[{"id":0,"score":0},{"id":1,"score":10},{"id":2,"score":20}]"#
+    );
+
+    // Debug output only says whether the cache is enabled, and clones keep using the cache.
+    let debug = format!("{:?}", serializer);
+    assert!(debug.contains("cache_map_keys: true"), "{}", debug);
+    assert!(!debug.contains("score"), "{}", debug);
+    let clone = serializer.clone().sort_map_keys(true);
+    let value = rows.serialize(&clone).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"[{"id":0,"score":0},{"id":1,"score":10},{"id":2,"score":20}]"#
+    );
+
+    // Newtype string keys go through the cache too, while other keys are unaffected.
+    let src = btreemap! { Key("a".to_string()) => 1, Key("b".to_string()) => 2 };
+    let value = src.serialize(&serializer).unwrap();
+    assert_eq!(js_sys::JSON::stringify(&value).unwrap(), r#"{"a":1,"b":2}"#);
+    let value = btreemap! { 1 => "a", 2 => "b" }
+        .serialize(&serializer)
+        .unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&value).unwrap(),
+        r#"{"1":"a","2":"b"}"#
+    );
+    let src = btreemap! { (1, "x".to_string()) => true };
+    let value = src
+        .serialize(&Serializer::new().cache_map_keys(true))
+        .unwrap();
+    let key = value
+        .dyn_ref::<js_sys::Map>()
+        .unwrap()
+        .keys()
+        .next()
+        .unwrap()
+        .value();
+    assert_eq!(js_sys::JSON::stringify(&key).unwrap(), r#"[1,"x"]"#);
+}
+
+#[wasm_bindgen_test]
+fn errors_as_js_errors() {
+    let as_js_error = |error: Error| JsValue::from(error).dyn_into::<js_sys::Error>().unwrap();
+
+    let error = as_js_error(from_value::<i32>("x".into()).unwrap_err());
+    assert_eq!(error.name(), "SerdeWasmBindgenError");
+    assert_eq!(error.message(), r#"invalid type: string "x", expected i32"#);
+    assert!(error.cause().is_undefined());
+
+    let error = as_js_error(Error::custom("failed"));
+    assert_eq!(error.name(), "SerdeWasmBindgenError");
+    assert_eq!(error.message(), "failed");
+    assert_eq!(error.to_string(), "SerdeWasmBindgenError: failed");
+
+    // JavaScript exceptions are kept as the cause.
+    let cause = js_sys::TypeError::new("bad");
+    let error = as_js_error(Error::from(JsValue::from(&cause)));
+    assert_eq!(error.name(), "SerdeWasmBindgenError");
+    assert_eq!(error.message(), "bad");
+    assert_eq!(error.cause(), JsValue::from(cause));
+
+    let error = as_js_error(Error::from(JsValue::from(42)));
+    assert_eq!(error.message(), "42");
+    assert_eq!(error.cause(), 42);
+
+    let serializer = Serializer::new()
+        .stringify_map_keys(true)
+        .serialize_large_number_types_as_bigints(true);
+    let error = btreemap! { vec![1_u64] => 1 }
+        .serialize(&serializer)
+        .unwrap_err();
+    let error = as_js_error(error);
+    assert_eq!(error.name(), "SerdeWasmBindgenError");
+    assert!(error.cause().is_instance_of::<js_sys::TypeError>());
+}
+
+#[wasm_bindgen_test]
+fn maps_preserve_order() {
+    let keys = |map: IndexMap<String, u32>| map.into_iter().collect::<Vec<_>>();
+    let expected = vec![
+        ("zebra".to_string(), 1),
+        ("apple".to_string(), 2),
+        ("mango".to_string(), 3),
+    ];
+
+    // Both objects and ES2015 Maps are read in their insertion order.
+    let object = js_sys::JSON::parse(r#"{"zebra": 1, "apple": 2, "mango": 3}"#).unwrap();
+    assert_eq!(keys(from_value(object).unwrap()), expected);
+
+    let map = js_sys::Map::new();
+    map.set(&"zebra".into(), &1.into());
+    map.set(&"apple".into(), &2.into());
+    map.set(&"mango".into(), &3.into());
+    assert_eq!(keys(from_value(map.into()).unwrap()), expected);
+
+    // Except that JavaScript itself always lists integer-like object keys first, in ascending order.
+    let object = js_sys::JSON::parse(r#"{"b": 1, "2": 2, "a": 3, "1": 4}"#).unwrap();
+    assert_eq!(
+        keys(from_value(object).unwrap()),
+        [
+            ("1".to_string(), 4),
+            ("2".to_string(), 2),
+            ("b".to_string(), 1),
+            ("a".to_string(), 3),
+        ]
+    );
+
+    // And the order is kept on the way back too.
+    let src: IndexMap<String, u32> = expected.into_iter().collect();
+    let object = src
+        .serialize(&Serializer::new().serialize_maps_as_objects(true))
+        .unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&object).unwrap(),
+        r#"{"zebra":1,"apple":2,"mango":3}"#
+    );
+    assert_eq!(from_value::<IndexMap<String, u32>>(object).unwrap(), src);
+}
+
+#[wasm_bindgen_test]
+fn maps_missing_values() {
+    let src = btreemap! {
+        "some".to_string() => Some(1),
+        "none".to_string() => None,
+    };
+    let some = JsValue::from("some");
+    let none = JsValue::from("none");
+
+    for (mode, expected) in [
+        (MissingMapValues::Inherit, Some(JsValue::UNDEFINED)),
+        (MissingMapValues::Undefined, Some(JsValue::UNDEFINED)),
+        (MissingMapValues::Null, Some(JsValue::NULL)),
+        (MissingMapValues::Omit, None),
+    ] {
+        let serializer = Serializer::new().missing_map_values(mode);
+
+        let map = src
+            .serialize(&serializer)
+            .unwrap()
+            .dyn_into::<js_sys::Map>()
+            .unwrap();
+        assert_eq!(map.get(&some), 1);
+        assert_eq!(map.has(&none), expected.is_some(), "{:?}", mode);
+        assert_eq!(
+            map.get(&none),
+            expected.clone().unwrap_or(JsValue::UNDEFINED)
+        );
+        // Omitted entries are naturally lost on the way back.
+        let mut restored = src.clone();
+        if expected.is_none() {
+            restored.remove("none");
+        }
+        assert_eq!(
+            from_value::<BTreeMap<String, Option<i32>>>(map.into()).unwrap(),
+            restored
+        );
+
+        let object = src
+            .serialize(&serializer.serialize_maps_as_objects(true))
+            .unwrap();
+        assert_eq!(Reflect::get(&object, &some).unwrap(), 1);
+        assert_eq!(
+            Reflect::has(&object, &none).unwrap(),
+            expected.is_some(),
+            "{:?}",
+            mode
+        );
+        assert_eq!(
+            Reflect::get(&object, &none).unwrap(),
+            expected.unwrap_or(JsValue::UNDEFINED)
+        );
+        assert_eq!(
+            from_value::<BTreeMap<String, Option<i32>>>(object).unwrap(),
+            restored
+        );
+    }
+
+    // `Inherit` follows `serialize_missing_as_null`, and the others override it.
+    let object = src.serialize(&Serializer::json_compatible()).unwrap();
+    assert_eq!(Reflect::get(&object, &none).unwrap(), JsValue::NULL);
+    let object = src
+        .serialize(&Serializer::json_compatible().missing_map_values(MissingMapValues::Undefined))
+        .unwrap();
+    assert_eq!(js_sys::JSON::stringify(&object).unwrap(), r#"{"some":1}"#);
+
+    // Only `None` counts as missing, not other values serialized to `null` or `undefined`.
+    let src = btreemap! {
+        "none" => None,
+        "some_none" => Some(None),
+        "some_some" => Some(Some(1)),
+    };
+    let serializer = Serializer::new()
+        .serialize_some_none_as_null(true)
+        .serialize_maps_as_objects(true)
+        .missing_map_values(MissingMapValues::Omit);
+    let object = src.serialize(&serializer).unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&object).unwrap(),
+        r#"{"some_none":null,"some_some":1}"#
+    );
+    let object = src
+        .serialize(&serializer.missing_map_values(MissingMapValues::Null))
+        .unwrap();
+    assert!(Reflect::has(&object, &"none".into()).unwrap());
+    assert!(Reflect::get(&object, &"none".into()).unwrap().is_null());
+
+    let src = btreemap! {
+        "unit" => PreservedValue(JsValue::UNDEFINED),
+        "null" => PreservedValue(JsValue::NULL),
+    };
+    let object = src
+        .serialize(
+            &Serializer::new()
+                .serialize_maps_as_objects(true)
+                .missing_map_values(MissingMapValues::Omit),
+        )
+        .unwrap();
+    assert_eq!(
+        Reflect::get(&object, &"null".into()).unwrap(),
+        JsValue::NULL
+    );
+    assert!(Reflect::has(&object, &"unit".into()).unwrap());
+    let units = btreemap! { "unit" => () }
+        .serialize(&Serializer::new().missing_map_values(MissingMapValues::Omit))
+        .unwrap()
+        .dyn_into::<js_sys::Map>()
+        .unwrap();
+    assert!(units.has(&"unit".into()));
+}
+
+#[wasm_bindgen_test]
+fn shared_bytes() {
+    let bytes: Vec<u8> = (0..=255).collect();
+    let view = unsafe { serde_wasm_bindgen::to_shared_bytes(&bytes) };
+    // A view into the Wasm memory rather than a copy.
+    assert_eq!(
+        JsValue::from(view.buffer()),
+        wasm_bindgen::memory()
+            .unchecked_into::<js_sys::WebAssembly::Memory>()
+            .buffer()
+    );
+    assert_eq!(view.byte_offset() as usize, bytes.as_ptr() as usize);
+    let copy = view.slice(0, view.length());
+    drop(bytes);
+    assert_eq!(copy.to_vec(), (0..=255).collect::<Vec<u8>>());
+}
+
+#[wasm_bindgen_test]
+fn json_values() {
+    use serde_json::{json, Value};
+
+    let to_json = |value: JsValue| from_value::<Value>(value).unwrap();
+
+    assert_eq!(to_json(JsValue::NULL), Value::Null);
+    assert_eq!(to_json(JsValue::UNDEFINED), Value::Null);
+    assert_eq!(to_json(JsValue::TRUE), json!(true));
+    assert_eq!(to_json(JsValue::from(42)), json!(42));
+    assert_eq!(to_json(JsValue::from(-1.5)), json!(-1.5));
+    assert_eq!(to_json(JsValue::from("abc")), json!("abc"));
+
+    let object = js_sys::JSON::parse(r#"{"a": [1, "x", null], "b": {"c": false}}"#).unwrap();
+    assert_eq!(
+        to_json(object),
+        json!({"a": [1, "x", null], "b": {"c": false}})
+    );
+
+    let map = js_sys::Map::new();
+    map.set(&"a".into(), &1.into());
+    map.set(&"b".into(), &Array::of1(&"x".into()));
+    assert_eq!(to_json(map.into()), json!({"a": 1, "b": ["x"]}));
+
+    // Byte buffers become arrays of numbers, same as when serde_json serializes bytes.
+    let bytes = js_sys::Uint8Array::from(&[1_u8, 2, 255][..]);
+    assert_eq!(to_json(bytes.clone().into()), json!([1, 2, 255]));
+    assert_eq!(to_json(bytes.buffer().into()), json!([1, 2, 255]));
+
+    assert_eq!(to_json(BigInt::from(7).into()), json!(7));
+    assert_eq!(to_json(BigInt::from(u64::MAX).into()), json!(u64::MAX));
+    assert_eq!(to_json(BigInt::from(i64::MIN).into()), json!(i64::MIN));
+    from_value::<Value>(BigInt::from(u64::MAX as i128 + 1).into()).unwrap_err();
+
+    // Objects without a prototype and class instances are objects too.
+    let object = Object::create(JsValue::NULL.unchecked_ref::<Object>());
+    Reflect::set(&object, &"a".into(), &1.into()).unwrap();
+    assert_eq!(to_json(object.into()), json!({"a": 1}));
+    let object = js_sys::eval("new (class { constructor() { this.a = 1; } })()").unwrap();
+    assert_eq!(to_json(object), json!({"a": 1}));
+
+    // Sets and other iterables are still ambiguous, as are non-data values.
+    from_value::<Value>(js_sys::Set::new(&Array::of1(&1.into())).into()).unwrap_err();
+    from_value::<Value>(
+        js_sys::eval("({ a: 1, [Symbol.iterator]: [][Symbol.iterator] })").unwrap(),
+    )
+    .unwrap_err();
+    from_value::<Value>(JsValue::symbol(None)).unwrap_err();
+
+    // Internally tagged enums go through the same code path, so they can come from a Map now too.
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(tag = "type")]
+    enum Tagged {
+        A { x: i32 },
+    }
+    let map = js_sys::Map::new();
+    map.set(&"type".into(), &"A".into());
+    map.set(&"x".into(), &1.into());
+    assert_eq!(
+        from_value::<Tagged>(map.into()).unwrap(),
+        Tagged::A { x: 1 }
+    );
+}
+
+#[wasm_bindgen_test]
+fn preserved_values() {
+    #[derive(Serialize, Deserialize)]
+    struct Widget {
+        id: u32,
+        element: PreservedValue,
+    }
+
+    let element: JsValue = Object::new().into();
+    let value = to_value(&Widget {
+        id: 1,
+        element: PreservedValue(element.clone()),
+    })
+    .unwrap();
+    assert_eq!(Reflect::get(&value, &"id".into()).unwrap(), 1);
+    // Same object by identity, not a copy.
+    assert_eq!(Reflect::get(&value, &"element".into()).unwrap(), element);
+
+    let widget: Widget = from_value(value).unwrap();
+    assert_eq!(widget.id, 1);
+    assert_eq!(widget.element.0, element);
+
+    // Values that can't go through Serde at all are passed through too.
+    let symbol = JsValue::symbol(None);
+    let restored: PreservedValue = from_value(symbol.clone()).unwrap();
+    assert_eq!(restored.0, symbol);
+    assert_eq!(to_value(&restored).unwrap(), symbol);
+
+    // Other deserializers can't make up a value, even from numbers that look like handles.
+    use serde::de::IntoDeserializer;
+    for handle in [0_u32, 1, 128, 5000] {
+        let deserializer: serde::de::value::U32Deserializer<serde::de::value::Error> =
+            handle.into_deserializer();
+        PreservedValue::deserialize(deserializer).unwrap_err();
+    }
+    PreservedValue::deserialize(().into_deserializer())
+        .map_err(|err: serde::de::value::Error| err)
+        .unwrap_err();
+
+    // Other serializers see a unit value.
+    assert_eq!(serde_json::to_string(&restored).unwrap(), "null");
+
+    // And the reserved name doesn't let anything else through our serializer.
+    struct Forged;
+
+    impl Serialize for Forged {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_newtype_struct("1fc430ca-5b7f-4295-92de-33cf2b145d38", &5000_u32)
+        }
+    }
+
+    to_value(&Forged).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn typed_array_map_values() {
+    use serde_wasm_bindgen::Float32Slice;
+
+    let (a, b) = (vec![1.5_f32, -2.0], vec![0.1_f32]);
+    let src = hashmap! {
+        "a".to_string() => Float32Slice(&a),
+        "b".to_string() => Float32Slice(&b),
+    };
+    let expected = hashmap! { "a".to_string() => a.clone(), "b".to_string() => b.clone() };
+
+    let check = |value: JsValue| {
+        let restored: HashMap<String, Vec<f32>> = from_value(value).unwrap();
+        assert_eq!(restored, expected);
+    };
+
+    // Both in `Map`s and objects.
+    let value = to_value(&src).unwrap();
+    let map = value.dyn_ref::<js_sys::Map>().unwrap();
+    for key in ["a", "b"] {
+        assert!(map
+            .get(&key.into())
+            .is_instance_of::<js_sys::Float32Array>());
+    }
+    let array: js_sys::Float32Array = map.get(&"a".into()).unchecked_into();
+    assert_eq!(array.to_vec(), a);
+    check(value);
+
+    let value = src
+        .serialize(&Serializer::new().serialize_maps_as_objects(true))
+        .unwrap();
+    for key in ["a", "b"] {
+        assert!(Reflect::get(&value, &key.into())
+            .unwrap()
+            .is_instance_of::<js_sys::Float32Array>());
+    }
+    check(value);
+
+    // And in struct fields.
+    #[derive(Serialize)]
+    struct Mesh<'a> {
+        vertices: Float32Slice<'a>,
+    }
+
+    let value = to_value(&Mesh {
+        vertices: Float32Slice(&a),
+    })
+    .unwrap();
+    assert!(Reflect::get(&value, &"vertices".into())
+        .unwrap()
+        .is_instance_of::<js_sys::Float32Array>());
+}
+
+#[wasm_bindgen_test]
+fn zero_padded_numbers() {
+    test_via_into(ZeroPadded::<4>(42), "0042");
+    test_via_into(ZeroPadded::<4>(0), "0000");
+    test_via_into(ZeroPadded::<4>(9999), "9999");
+    test_via_into(ZeroPadded::<0>(7), "7");
+
+    // Longer values are kept as they are rather than truncated.
+    test_via_into(ZeroPadded::<4>(12345), "12345");
+    test_via_into(ZeroPadded::<2>(u64::MAX), u64::MAX.to_string());
+
+    // Leading zeros are ignored regardless of the width.
+    for &s in &["42", "0042", "000000000042"] {
+        assert_eq!(
+            from_value::<ZeroPadded<4>>(JsValue::from(s)).unwrap(),
+            ZeroPadded(42)
+        );
+    }
+
+    for &s in &["", "-1", "+1", " 42", "4.2", "0x2a", "18446744073709551616"] {
+        from_value::<ZeroPadded<4>>(JsValue::from(s)).unwrap_err();
+    }
+    from_value::<ZeroPadded<4>>(JsValue::from(42)).unwrap_err();
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        code: ZeroPadded<6>,
+    }
+
+    test_via_json(Record {
+        code: ZeroPadded(123),
+    });
+    assert_eq!(
+        js_sys::JSON::stringify(&to_value(&Record { code: 123.into() }).unwrap()).unwrap(),
+        r#"{"code":"000123"}"#
+    );
+}
+
+#[wasm_bindgen_test]
+fn enums_as_bools() {
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+    enum State {
+        On,
+        #[default]
+        Off,
+    }
+
+    test_via_into(BoolEnum(State::On), true);
+    test_via_into(BoolEnum(State::Off), false);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Light {
+        state: BoolEnum<State>,
+    }
+
+    test_via_json(Light {
+        state: BoolEnum(State::On),
+    });
+    test_via_json(Light {
+        state: BoolEnum(State::Off),
+    });
+
+    // Renamed variants are found by their serialized names.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Renamed {
+        Yes,
+        No,
+    }
+
+    test_via_into(BoolEnum(Renamed::Yes), true);
+    test_via_into(BoolEnum(Renamed::No), false);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Three {
+        A,
+        B,
+        C,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum NotUnit {
+        A,
+        B(i32),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct NotEnum(i32);
+
+    const EXPECTED: &str =
+        "SerdeWasmBindgenError: BoolEnum requires an enum with exactly two unit variants";
+
+    fn assert_rejected<T: Serialize>(value: T) {
+        assert_eq!(
+            to_value(&BoolEnum(value)).unwrap_err().to_string(),
+            EXPECTED
+        );
+    }
+
+    // Serialization only looks at the variant index, so it can't reject the first two variants
+    // of a longer enum, but deserialization checks the whole enum.
+    assert!(to_value(&BoolEnum(Three::A)).unwrap().is_truthy());
+    assert!(to_value(&BoolEnum(NotUnit::A)).unwrap().is_truthy());
+    assert_rejected(Three::C);
+    assert_rejected(NotUnit::B(1));
+    assert_rejected(NotEnum(1));
+
+    fn assert_rejected_from_value<T: DeserializeOwned + Debug>() {
+        assert_eq!(
+            from_value::<BoolEnum<T>>(JsValue::TRUE)
+                .unwrap_err()
+                .to_string(),
+            EXPECTED
+        );
+    }
+
+    assert_rejected_from_value::<Three>();
+    assert_rejected_from_value::<NotUnit>();
+    assert_rejected_from_value::<NotEnum>();
+
+    from_value::<BoolEnum<State>>(JsValue::from("On")).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn try_from_value_collects_errors() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Inner {
+        x: i32,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Form {
+        name: String,
+        age: u8,
+        email: Option<String>,
+        inner: Inner,
+        tags: Vec<String>,
+    }
+
+    let fields = |errors: Vec<(String, Error)>| {
+        errors
+            .into_iter()
+            .map(|(field, _)| field)
+            .collect::<Vec<_>>()
+    };
+
+    let value = js_sys::JSON::parse(
+        r#"{"name": "Alice", "age": 30, "email": "a@b.c", "inner": {"x": 1}, "tags": ["a"]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        serde_wasm_bindgen::try_from_value::<Form>(value).unwrap(),
+        Form {
+            name: "Alice".to_owned(),
+            age: 30,
+            email: Some("a@b.c".to_owned()),
+            inner: Inner { x: 1 },
+            tags: vec!["a".to_owned()],
+        }
+    );
+
+    let value = js_sys::JSON::parse(
+        r#"{"name": 1, "age": 300, "email": null, "inner": {"x": "1"}, "tags": [1]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        fields(serde_wasm_bindgen::try_from_value::<Form>(value).unwrap_err()),
+        ["name", "age", "inner", "tags"]
+    );
+
+    // Missing required fields are reported too, while optional ones are fine.
+    let value = js_sys::JSON::parse(r#"{"name": 1, "inner": {"x": 1}, "tags": []}"#).unwrap();
+    assert_eq!(
+        fields(serde_wasm_bindgen::try_from_value::<Form>(value).unwrap_err()),
+        ["name", "age"]
+    );
+
+    // Fields of a top-level `Map` are collected the same way.
+    let map = js_sys::Map::new();
+    let value = js_sys::JSON::parse(
+        r#"{"name": 1, "age": 300, "email": null, "inner": {"x": "1"}, "tags": ["a"]}"#,
+    )
+    .unwrap();
+    for entry in Object::entries(value.unchecked_ref()).iter() {
+        let entry = Array::from(&entry);
+        map.set(&entry.get(0), &entry.get(1));
+    }
+    assert_eq!(
+        fields(serde_wasm_bindgen::try_from_value::<Form>(map.into()).unwrap_err()),
+        ["name", "age", "inner"]
+    );
+
+    let errors = serde_wasm_bindgen::try_from_value::<Form>(JsValue::from(1)).unwrap_err();
+    assert_eq!(fields(errors), [""]);
+}
+
+#[wasm_bindgen_test]
+fn from_value_tracking_presence() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Inner {
+        x: Option<i32>,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Patch {
+        name: Option<String>,
+        #[serde(rename = "emailAddress")]
+        email: Option<String>,
+        age: Option<u8>,
+        inner: Option<Inner>,
+    }
+
+    let (patch, present) = serde_wasm_bindgen::from_value_tracking_presence::<Patch>(
+        js_sys::JSON::parse(r#"{"name": "Alice", "emailAddress": null, "inner": {"x": 1}}"#)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        patch,
+        Patch {
+            name: Some("Alice".to_owned()),
+            email: None,
+            age: None,
+            inner: Some(Inner { x: Some(1) }),
+        }
+    );
+    // `emailAddress` is explicitly cleared, while `age` is left as is. Nested fields aren't tracked.
+    assert_eq!(
+        present,
+        hashset! {
+            "name".to_owned(),
+            "emailAddress".to_owned(),
+            "inner".to_owned(),
+        }
+    );
+
+    // `undefined` is the same as absent.
+    let value = Object::new();
+    Reflect::set(&value, &"age".into(), &JsValue::UNDEFINED).unwrap();
+    let (_, present) =
+        serde_wasm_bindgen::from_value_tracking_presence::<Patch>(value.into()).unwrap();
+    assert!(present.is_empty());
+
+    // Fields of a top-level `Map` are tracked the same way.
+    let map = js_sys::Map::new();
+    map.set(&"name".into(), &"Bob".into());
+    map.set(&"emailAddress".into(), &JsValue::NULL);
+    map.set(&"age".into(), &JsValue::UNDEFINED);
+    let (patch, present) =
+        serde_wasm_bindgen::from_value_tracking_presence::<Patch>(map.into()).unwrap();
+    assert_eq!(patch.name.as_deref(), Some("Bob"));
+    assert_eq!(
+        present,
+        hashset! { "name".to_owned(), "emailAddress".to_owned() }
+    );
+
+    serde_wasm_bindgen::from_value_tracking_presence::<Patch>(JsValue::from(1)).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn null_prototype_objects() {
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Struct {
+        a: i32,
+    }
+
+    let serializer = Serializer::new()
+        .serialize_maps_as_objects(true)
+        .null_prototype_objects(true);
+
+    let value = Struct { a: 1 }.serialize(&serializer).unwrap();
+    assert!(Object::get_prototype_of(&value).is_null());
+    assert_json(value, Struct { a: 1 });
+
+    let src = btreemap! {
+        "__proto__".to_string() => Struct { a: 1 },
+        "constructor".to_string() => Struct { a: 2 },
+    };
+    let value = src.serialize(&serializer).unwrap();
+    assert!(Object::get_prototype_of(&value).is_null());
+    let keys = Object::keys(value.unchecked_ref::<Object>());
+    assert_eq!(keys.length(), 2);
+    assert_eq!(keys.get(0), "__proto__");
+    assert_json(value, src.clone());
+
+    // Enum wrappers and tagged objects have no prototype either.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum Enum {
+        Unit,
+        Newtype(i32),
+        Tuple(i32, i32),
+        Struct { a: i32 },
+    }
+
+    let variants = [
+        Enum::Unit,
+        Enum::Newtype(1),
+        Enum::Tuple(1, 2),
+        Enum::Struct { a: 1 },
+    ];
+    for (serializer, skip_unit) in [
+        (Serializer::new().null_prototype_objects(true), true),
+        (
+            Serializer::new()
+                .null_prototype_objects(true)
+                .adjacent_variant_tag(Some(("type", "data"))),
+            false,
+        ),
+        (
+            Serializer::new()
+                .null_prototype_objects(true)
+                .indexed_variant_tag(Some(("kind", "index"))),
+            false,
+        ),
+    ] {
+        for variant in &variants {
+            if skip_unit && *variant == Enum::Unit {
+                continue;
+            }
+            let value = variant.serialize(&serializer).unwrap();
+            assert!(Object::get_prototype_of(&value).is_null(), "{:?}", variant);
+        }
+    }
+
+    // Plain objects get a polluted prototype instead.
+    let value = src
+        .serialize(&Serializer::new().serialize_maps_as_objects(true))
+        .unwrap();
+    assert_eq!(Object::keys(value.unchecked_ref::<Object>()).length(), 1);
+    assert_eq!(
+        Reflect::get(&Object::get_prototype_of(&value), &"a".into()).unwrap(),
+        1
+    );
+}
+
+#[wasm_bindgen_test]
+fn serialize_json_compatible() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+    struct Struct {
+        a: HashMap<String, ()>,
+        b: Option<i32>,
+    }
+    let x = Struct {
+        a: hashmap! {
+            "foo".to_string() => (),
+            "bar".to_string() => (),
+        },
+        b: None,
+    };
+    test_via_json_with_config(x, Serializer::json_compatible());
+}
+
+#[wasm_bindgen_test]
+fn maps_objects_object_key() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+    struct Struct<A, B> {
+        a: A,
+        b: B,
+    }
+
+    let serializer = Serializer::new().serialize_maps_as_objects(true);
 
     let src = hashmap! {
         Struct {
@@ -591,3 +3370,115 @@ fn maps_objects_object_key() {
         Error::custom("Map key is not a string and cannot be an object key").to_string()
     );
 }
+
+#[wasm_bindgen_test]
+fn durations_as_millis() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Timeout(#[serde(with = "serde_wasm_bindgen::as_millis")] Duration);
+
+    test_via_into(Timeout(Duration::from_millis(0)), 0_f64);
+    test_via_into(Timeout(Duration::from_millis(1234)), 1234_f64);
+
+    // Sub-millisecond precision is truncated on the way to JS...
+    assert_eq!(
+        to_value(&Timeout(Duration::from_micros(1999))).unwrap(),
+        1_f64
+    );
+    assert_eq!(
+        to_value(&Timeout(Duration::from_nanos(999_999))).unwrap(),
+        0_f64
+    );
+
+    // ...but fractional milliseconds coming from JS are preserved.
+    assert_eq!(
+        from_value::<Timeout>(JsValue::from_f64(1.5)).unwrap(),
+        Timeout(Duration::from_micros(1500))
+    );
+
+    // Values beyond the safe integer range are rejected in both directions.
+    to_value(&Timeout(Duration::from_millis(9_007_199_254_740_992))).unwrap_err();
+    to_value(&Timeout(Duration::MAX)).unwrap_err();
+    from_value::<Timeout>(JsValue::from_f64(9_007_199_254_740_992.0)).unwrap_err();
+    from_value::<Timeout>(JsValue::from_f64(-1.0)).unwrap_err();
+    from_value::<Timeout>(JsValue::from_f64(f64::NAN)).unwrap_err();
+}
+
+#[wasm_bindgen_test]
+fn durations_in_units() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Secs(#[serde(with = "serde_wasm_bindgen::duration_secs")] Duration);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Millis(#[serde(with = "serde_wasm_bindgen::duration_millis")] Duration);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Nanos(#[serde(with = "serde_wasm_bindgen::duration_nanos")] Duration);
+
+    test_via_into(Secs(Duration::from_secs(0)), 0_f64);
+    test_via_into(Secs(Duration::from_secs(42)), 42_f64);
+    test_via_into(Secs(Duration::from_millis(1500)), 1.5);
+    test_via_into(Secs(Duration::from_millis(250)), 0.25);
+    assert_eq!(
+        from_value::<Secs>(JsValue::from_f64(1.5)).unwrap(),
+        Secs(Duration::from_millis(1500))
+    );
+    assert_eq!(
+        from_value::<Secs>(JsValue::from_f64(0.000_000_001)).unwrap(),
+        Secs(Duration::from_nanos(1))
+    );
+
+    test_via_into(Millis(Duration::from_millis(1234)), 1234_f64);
+    test_via_into(Millis(Duration::from_micros(1500)), 1.5);
+    test_via_into(Millis(Duration::from_secs(3)), 3000_f64);
+    // Fractions rounding up to a whole unit are carried over.
+    assert_eq!(
+        from_value::<Millis>(JsValue::from_f64(1.999_999_999_9)).unwrap(),
+        Millis(Duration::from_millis(2))
+    );
+    assert_eq!(
+        from_value::<Millis>(JsValue::from_f64(999.999_999_9)).unwrap(),
+        Millis(Duration::from_secs(1))
+    );
+    assert_eq!(
+        from_value::<Secs>(JsValue::from_f64(0.999_999_999_9)).unwrap(),
+        Secs(Duration::from_secs(1))
+    );
+
+    test_via_into(Nanos(Duration::from_nanos(0)), 0_f64);
+    test_via_into(Nanos(Duration::new(1, 5)), 1_000_000_005_f64);
+    // Nanoseconds beyond the safe integer range become a `BigInt`...
+    test_via_into(
+        Nanos(Duration::from_nanos(9_007_199_254_740_991)),
+        9_007_199_254_740_991_f64,
+    );
+    test_via_into(
+        Nanos(Duration::from_nanos(9_007_199_254_740_992)),
+        BigInt::from(9_007_199_254_740_992_u64),
+    );
+    test_via_into(
+        Nanos(Duration::from_nanos(u64::MAX)),
+        BigInt::from(u64::MAX),
+    );
+    test_via_into_with_config(
+        Nanos(Duration::from_nanos(1)),
+        BigInt::from(1),
+        &Serializer::new().serialize_large_number_types_as_bigints(true),
+    );
+    // ...while other serializers get a plain integer.
+    assert_eq!(
+        serde_json::to_string(&Nanos(Duration::from_nanos(u64::MAX))).unwrap(),
+        u64::MAX.to_string()
+    );
+    // ...and fractional nanoseconds are rejected.
+    from_value::<Nanos>(JsValue::from_f64(1.5)).unwrap_err();
+    to_value(&Nanos(Duration::MAX)).unwrap_err();
+
+    // Negative and non-safe numbers are rejected by all units.
+    for value in &[-1.0, f64::NAN, 9_007_199_254_740_992.0] {
+        from_value::<Secs>(JsValue::from_f64(*value)).unwrap_err();
+        from_value::<Millis>(JsValue::from_f64(*value)).unwrap_err();
+        from_value::<Nanos>(JsValue::from_f64(*value)).unwrap_err();
+    }
+    to_value(&Secs(Duration::MAX)).unwrap_err();
+    to_value(&Millis(Duration::MAX)).unwrap_err();
+}